@@ -38,7 +38,7 @@ impl Consts {
             s_impl_core = s_impl_core + &format!("\n\t\t\tconst {}: &'static[Self] = &{};", nam, val);
         }
         let s_trait = format!("pub trait FloatConst: Float {{{}\n}}", &s_trait_core);
-        let s_impl = format!("macro_rules! impl_float_const {{\n\t($type:ty) => {{\n\t\timpl FloatConst for $type {{{}\n\t\t}}\n\t}};\n}}", s_impl_core);
+        let s_impl = format!("macro_rules! impl_float_const {{\n\t($type:ty) => {{\n\t\t#[allow(clippy::excessive_precision)]\n\t\timpl FloatConst for $type {{{}\n\t\t}}\n\t}};\n}}", s_impl_core);
         let s = format!("use crate::float::Float;\n\n{}\n\n{}\n\nimpl_float_const!(f64);\nimpl_float_const!(f32);", s_trait, s_impl);
         let _ = save_to_file("../scicompt/src/consts.rs", &s);
     }
@@ -1,3 +1,6 @@
+// The tables are copied with their published digits; f64 rounds them when the values are parsed.
+#![allow(clippy::excessive_precision)]
+
 mod consts;
 mod io;
 
@@ -7,6 +10,488 @@ fn main() {
     let mut c = Consts::new();
     c.add_val("TWO_PI_SQRT", (std::f64::consts::PI * 2.0).sqrt());
     c.add_val("TWO_PI_SQRT_INV", 1.0 / (std::f64::consts::PI * 2.0).sqrt());
+    c.add_val("LN_TWO_PI_SQRT", (std::f64::consts::PI * 2.0).sqrt().ln());
+    c.add_val("LANCZOS_G", 4.7421875);
+    c.add_val("EULER_GAMMA", 0.5772156649015329);
     c.add_vec("DUMMY", &vec![1.0, 2.0, 3.0]);
+    c.add_vec("LANCZOS_COEF", &vec![
+        0.999999999999997092,
+        57.1562356658629235,
+        -59.5979603554754912,
+        14.1360979747417471,
+        -0.491913816097620199,
+        0.339946499848118887e-4,
+        0.465236289270485756e-4,
+        -0.983744753048795646e-4,
+        0.158088703224912494e-3,
+        -0.210264441724104883e-3,
+        0.217439618115212643e-3,
+        -0.164318106536763890e-3,
+        0.844182239838527433e-4,
+        -0.261908384015814087e-4,
+        0.368991826595316234e-5,
+    ]);
+    c.add_vec("BERNOULLI_2K", &vec![
+        1.0 / 6.0,
+        -1.0 / 30.0,
+        1.0 / 42.0,
+        -1.0 / 30.0,
+        5.0 / 66.0,
+        -691.0 / 2730.0,
+        7.0 / 6.0,
+        -3617.0 / 510.0,
+        43867.0 / 798.0,
+    ]);
+    c.add_vec("ERFC_CHEB", &vec![
+        -1.3026537197817094,
+        6.4196979235649026e-1,
+        1.9476473204185836e-2,
+        -9.561514786808631e-3,
+        -9.46595344482036e-4,
+        3.66839497852761e-4,
+        4.2523324806907e-5,
+        -2.0278578112534e-5,
+        -1.624290004647e-6,
+        1.303655835580e-6,
+        1.5626441722e-8,
+        -8.5238095915e-8,
+        6.529054439e-9,
+        5.059343495e-9,
+        -9.91364156e-10,
+        -2.27365122e-10,
+        9.6467911e-11,
+        2.394038e-12,
+        -6.886027e-12,
+        8.94487e-13,
+        3.13092e-13,
+        -1.12708e-13,
+        3.81e-16,
+        7.106e-15,
+        -1.523e-15,
+        -9.4e-17,
+        1.21e-16,
+        -2.8e-17,
+    ]);
+    // zeta(k) - 1 for k = 2, 3, ..., 40
+    c.add_vec("ZETA_M1", &vec![
+        0.6449340668482264,
+        0.2020569031595943,
+        0.08232323371113819,
+        0.03692775514336993,
+        0.01734306198444914,
+        0.008349277381922827,
+        0.00407735619794434,
+        0.0020083928260822143,
+        0.0009945751278180853,
+        0.0004941886041194645,
+        0.0002460865533080483,
+        0.00012271334757848915,
+        6.124813505870483e-05,
+        3.058823630702049e-05,
+        1.528225940865187e-05,
+        7.637197637899763e-06,
+        3.81729326499984e-06,
+        1.908212716553939e-06,
+        9.539620338727962e-07,
+        4.769329867878064e-07,
+        2.38450502727733e-07,
+        1.1921992596531106e-07,
+        5.960818905125948e-08,
+        2.980350351465228e-08,
+        1.4901554828365043e-08,
+        7.45071178983543e-09,
+        3.725334024788457e-09,
+        1.862659723513049e-09,
+        9.313274324196682e-10,
+        4.656629065033784e-10,
+        2.3283118336765053e-10,
+        1.164155017270052e-10,
+        5.820772087902701e-11,
+        2.9103850444971e-11,
+        1.4551921891041985e-11,
+        7.275959835057482e-12,
+        3.637979547378651e-12,
+        1.818989650307066e-12,
+        9.094947840263888e-13,
+    ]);
+    c.add_vec("BESSEL_I0_A", &vec![
+        0.33839763720473803,
+        -0.3046826723431984,
+        0.17162090152220877,
+        -0.09490109704804764,
+        0.04930528423967071,
+        -0.02373741480589947,
+        0.010546460394594998,
+        -0.004324309995050576,
+        0.0016394756169413357,
+        -0.0005763755745385824,
+        0.00018850288509584165,
+        -5.754195010082104e-05,
+        1.6448448070728896e-05,
+        -4.4167383584587505e-06,
+        1.1173875391201037e-06,
+        -2.670793853940612e-07,
+        6.046995022541919e-08,
+        -1.300025009986248e-08,
+        2.6598237246823866e-09,
+        -5.189795601635263e-10,
+        9.675809035373237e-11,
+        -1.726826291441556e-11,
+        2.95505266312964e-12,
+        -4.856446783111929e-13,
+        7.676185498604936e-14,
+        -1.1685332877993451e-14,
+        1.715391285555133e-15,
+        -2.431279846547955e-16,
+        3.3307945188222384e-17,
+        -4.4153416464793395e-18,
+        5.669178006921496e-19,
+    ]);
+    c.add_vec("BESSEL_I0_B", &vec![
+        0.4022452055070544,
+        0.0033691164782556943,
+        6.889758346916825e-05,
+        2.8913705208347567e-06,
+        2.0489185894690638e-07,
+        2.266668990498178e-08,
+        3.3962320257083865e-09,
+        4.94060238822497e-10,
+        1.1889147107846439e-11,
+        -3.1499165279632416e-11,
+        -1.3215811840447713e-11,
+        -1.7941785315068062e-12,
+        7.180124451383666e-13,
+        3.8527783827421426e-13,
+        1.54008621752141e-14,
+        -4.150569347287222e-14,
+        -9.554846698828307e-15,
+        3.8116806693526224e-15,
+        1.7725601330565263e-15,
+        -3.425485619677219e-16,
+        -2.8276239805165836e-16,
+        3.461222867697461e-17,
+        4.46562142029676e-17,
+        -4.830504485944182e-18,
+        -7.233180487874754e-18,
+        9.921475412173699e-19,
+        1.193650890845982e-18,
+    ]);
+    c.add_vec("BESSEL_I1_A", &vec![
+        0.12629359322181682,
+        -0.17641651835783406,
+        0.1026436586898471,
+        -0.05294598120809499,
+        0.024726449030626516,
+        -0.010564084894626197,
+        0.004156422944312888,
+        -0.0015135724506312532,
+        0.0005122859561685758,
+        -0.00016176081582589674,
+        4.781565107550054e-05,
+        -1.3273163656039436e-05,
+        3.4702513081376785e-06,
+        -8.568720264695455e-07,
+        2.0032947535521353e-07,
+        -4.445059128796328e-08,
+        9.381537386495773e-09,
+        -1.8872497517228294e-09,
+        3.625590281552117e-10,
+        -6.663489723502027e-11,
+        1.1736186298890901e-11,
+        -1.9839743977649436e-12,
+        3.223793365945575e-13,
+        -5.042185504727912e-14,
+        7.600684294735408e-15,
+        -1.1055969477353862e-15,
+        1.5536319577362005e-16,
+        -2.111421214358166e-17,
+        2.7779141127610464e-18,
+        -3.541581772542136e-19,
+    ]);
+    c.add_vec("BESSEL_I1_B", &vec![
+        0.38928811750914005,
+        -0.009761097491361469,
+        -0.00011058893876262371,
+        -3.882564808877691e-06,
+        -2.512236237870209e-07,
+        -2.6314688468895196e-08,
+        -3.835380385964237e-09,
+        -5.589743462196584e-10,
+        -1.8974958123505413e-11,
+        3.2526035830154884e-11,
+        1.4125807436613782e-11,
+        2.0356285441470896e-12,
+        -7.198551776245908e-13,
+        -4.0835511110921974e-13,
+        -2.1015418427726643e-14,
+        4.272440016711951e-14,
+        1.0420276984128802e-14,
+        -3.8144030724370075e-15,
+        -1.8803547755107825e-15,
+        3.3082023109209285e-16,
+        2.96262899764595e-16,
+        -3.209525921993424e-17,
+        -4.6503053684893586e-17,
+        4.414348323071708e-18,
+        7.517296310842105e-18,
+        -9.314178867326884e-19,
+        -1.242193275194891e-18,
+    ]);
+    c.add_vec("BESSEL_K0_A", &vec![
+        -0.2676636966169514,
+        0.3442898999246285,
+        0.0359799365153615,
+        0.001264615411446926,
+        2.286212103119452e-05,
+        2.5347910790261494e-07,
+        1.904516377220209e-09,
+        1.0349695257633625e-11,
+        4.2598161427910826e-14,
+        1.3744654358807508e-16,
+        3.5708965285083736e-19,
+    ]);
+    c.add_vec("BESSEL_K0_B", &vec![
+        1.2201515410329777,
+        -0.0314481013119645,
+        0.0015698838857300533,
+        -0.00012849549581627802,
+        1.39498137188765e-05,
+        -1.8317555227191195e-06,
+        2.766813639445015e-07,
+        -4.660489897687948e-08,
+        8.574034017414225e-09,
+        -1.6975345093890614e-09,
+        3.5773972814003283e-10,
+        -7.957489244477396e-11,
+        1.8559491149549264e-11,
+        -4.514597883374519e-12,
+        1.1403405882073441e-12,
+        -2.9800969231481784e-13,
+        8.032890775068375e-14,
+        -2.2275133267462965e-14,
+        6.340076476276646e-15,
+        -1.848593377920907e-15,
+        5.5120559994043335e-16,
+        -1.6782311257549006e-16,
+        5.2103917776435543e-17,
+        -1.6475805939842632e-17,
+        5.3004337711773354e-18,
+        -1.7331712005821001e-18,
+    ]);
+    c.add_vec("BESSEL_K1_A", &vec![
+        0.7626501136694739,
+        -0.3531559607765449,
+        -0.12261118082265715,
+        -0.006975723859639864,
+        -0.0001730288957513052,
+        -2.4334061415659684e-06,
+        -2.213387630734726e-08,
+        -1.4114883926335278e-10,
+        -6.666901694199329e-13,
+        -2.427449850519366e-15,
+        -7.023863479386288e-18,
+    ]);
+    c.add_vec("BESSEL_K1_B", &vec![
+        1.3603130952422213,
+        0.10392373657681724,
+        -0.002857816859622779,
+        0.00019521551847135162,
+        -1.936197974166083e-05,
+        2.406484947837217e-06,
+        -3.5019606030878126e-07,
+        5.7410841254500495e-08,
+        -1.0345762465678097e-08,
+        2.0150497551970347e-09,
+        -4.1903547593419254e-10,
+        9.218315187605315e-11,
+        -2.129967838427791e-11,
+        5.139639673482343e-12,
+        -1.2891739609498229e-12,
+        3.348419666052243e-13,
+        -8.976705182010146e-14,
+        2.4771544242195988e-14,
+        -7.0198370892147685e-15,
+        2.038703166239861e-15,
+        -6.057047270643018e-16,
+        1.8380935752430455e-16,
+        -5.689462849193648e-17,
+        1.7940510478863572e-17,
+        -5.7567444820733025e-18,
+        1.8778651901623268e-18,
+    ]);
+    c.add_vec("BESSEL_J0_A", &vec![
+        0.0019903555001601486,
+        -0.002620939138497025,
+        0.0008687721472062577,
+        -0.00017040584951557357,
+        2.204908639454817e-05,
+        -2.0248894980012834e-06,
+        1.3898025173428905e-07,
+        -7.406701254344167e-09,
+        3.1559927583611334e-10,
+        -1.1003860827939697e-11,
+        3.1989232970233477e-13,
+        -7.875041254709154e-15,
+        1.6632588015211278e-16,
+        -3.0475993756698376e-18,
+        4.8912290055811176e-20,
+    ]);
+    c.add_vec("BESSEL_J1_A", &vec![
+        0.00027895709887613106,
+        -0.0003115684036643562,
+        8.553646588540538e-05,
+        -1.4149356425623485e-05,
+        1.576470099225889e-06,
+        -1.269075502369425e-07,
+        7.747614077073057e-09,
+        -3.716577146675772e-10,
+        1.4395417669312554e-11,
+        -4.60007822413452e-13,
+        1.2341465589619718e-14,
+        -2.8205466239524856e-16,
+        5.558821065951572e-18,
+        -9.546930865755219e-20,
+        1.4418291781315051e-21,
+    ]);
+    c.add_vec("BESSEL_Y0_A", &vec![
+        0.03645469809116044,
+        -0.2783237094075825,
+        0.2960499990207148,
+        0.09825508408187864,
+        -0.10755155280627783,
+        0.031799074084414514,
+        -0.005161397105810715,
+        0.0005498525320039012,
+        -4.1996983149420134e-05,
+        2.4290361107923793e-06,
+        -1.1049969793472957e-07,
+        4.06651736597911e-09,
+        -1.2374148898289854e-10,
+        3.1685725528945945e-12,
+        -6.926956032431002e-14,
+        1.3086308625876684e-15,
+        -2.1586201986914482e-17,
+        3.136863182479938e-19,
+    ]);
+    c.add_vec("BESSEL_Y1_A", &vec![
+        0.038300769852423776,
+        -0.08182561412732826,
+        -0.0248677076121964,
+        0.047967452752746984,
+        -0.01852588451089802,
+        0.003680607687823511,
+        -0.0004627254060293369,
+        4.06940026958087e-05,
+        -2.6617695125295625e-06,
+        1.350602691325434e-07,
+        -5.483524110336276e-09,
+        1.8245086841229007e-10,
+        -5.070666636591129e-12,
+        1.1956162517587948e-13,
+        -2.423162442712473e-15,
+        4.268126513072962e-17,
+        -6.596060978723042e-19,
+    ]);
+    c.add_vec("BESSEL_P0", &vec![
+        0.9994603493475187,
+        -0.0005365220468132117,
+        3.0751847875194745e-06,
+        -5.1705945376060975e-08,
+        1.6306464635151382e-09,
+        -7.86409137723707e-11,
+        5.168262387349193e-12,
+        -4.3045788699253914e-13,
+        4.3265957431549404e-14,
+        -5.069034095935236e-15,
+        6.748072215733873e-16,
+        -1.0011513723467786e-16,
+        1.6305919233744186e-17,
+        -2.880866169482871e-18,
+    ]);
+    c.add_vec("BESSEL_Q0", &vec![
+        -0.12444683684269607,
+        0.0005470815954089319,
+        -5.9315987288485175e-06,
+        1.4377965798375193e-07,
+        -5.817532749493056e-09,
+        3.376097523734991e-10,
+        -2.565397936797308e-11,
+        2.404916100281365e-12,
+        -2.6690625482579414e-13,
+        3.4041800321963686e-14,
+        -4.87994410531204e-15,
+        7.729703176242605e-16,
+        -1.3348852171502517e-16,
+        2.4865952389390515e-17,
+        -4.952892629886516e-18,
+        1.0473158973776097e-18,
+        -2.336930172211422e-19,
+    ]);
+    c.add_vec("BESSEL_P1", &vec![
+        1.0009030408600137,
+        0.0008989898330859408,
+        -3.987284300488908e-06,
+        6.177633960644299e-08,
+        -1.8718907491063067e-09,
+        8.816898659582339e-11,
+        -5.704863640395645e-12,
+        4.699195515230542e-13,
+        -4.6842237839904895e-14,
+        5.452674896044717e-15,
+        -7.221180842274018e-16,
+        1.0667689114335412e-16,
+        -1.7312313216116335e-17,
+        3.0492991197665872e-18,
+    ]);
+    c.add_vec("BESSEL_Q1", &vec![
+        0.3742222965562826,
+        -0.0007702178839325664,
+        7.3108922063643636e-06,
+        -1.676782510726674e-07,
+        6.583354662120443e-09,
+        -3.749090950541556e-10,
+        2.8121750359748866e-11,
+        -2.61145253946232e-12,
+        2.8774212663332235e-13,
+        -3.649001916061838e-14,
+        5.206626366226707e-15,
+        -8.215318025458595e-16,
+        1.4141084390211833e-16,
+        -2.626761589838529e-17,
+        5.2192649196714085e-18,
+        -1.101261718787959e-18,
+    ]);
+    c.add_vec("BESSEL_GAM1", &vec![
+        -0.571011340185584,
+        0.006516511267073688,
+        0.0003087090173085368,
+        -3.470626964904318e-06,
+        6.943766448667449e-09,
+        3.67795398857441e-11,
+        -1.3563951023664248e-13,
+        -3.680298480635798e-17,
+    ]);
+    c.add_vec("BESSEL_GAM2", &vec![
+        0.9218702936504527,
+        -0.07685284084478668,
+        0.0012719271366545622,
+        -4.9717367041957395e-06,
+        -3.3126119768180853e-08,
+        2.42309579004827e-10,
+        -1.702377664251273e-13,
+        -1.4943667065169001e-15,
+        2.3826220476859634e-18,
+    ]);
+    // First two zeros of J0 then of J1, each split into a high and a low part
+    c.add_vec("BESSEL_ZEROS", &vec![
+        2.404825557695773,
+        -1.176691651530894e-16,
+        5.520078110286311,
+        8.088597146146722e-17,
+        3.8317059702075125,
+        -1.5269184090088067e-16,
+        7.015586669815619,
+        -9.414165653410389e-17,
+    ]);
     c.save();
-}
\ No newline at end of file
+}
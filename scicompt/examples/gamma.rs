@@ -0,0 +1,12 @@
+use scicompt::gamma::*;
+
+fn main() {
+    for &x in [0.5, 1.0, 2.5, 10.0, -1.5].iter() {
+        println!("gamma({}) = {}", x, gamma(x));
+        println!("ln_gamma({}) = {}", x, ln_gamma(x));
+        println!("digamma({}) = {}", x, digamma(x));
+        println!("trigamma({}) = {}", x, trigamma(x));
+        println!("polygamma(3, {}) = {}", x, polygamma(3, x));
+    }
+    println!("{:?}", gamma(4.5f32));
+}
@@ -0,0 +1,35 @@
+use ndarray::*;
+use scicompt::distribution::*;
+use scicompt::random::Rng;
+
+fn main() {
+    let truth = GaussianMixture::new(
+        arr1(&[0.6, 0.4]),
+        vec![
+            MultivariateGaussian::new(arr1(&[0.0, 0.0]), arr2(&[[1.0, 0.5], [0.5, 1.0]])).unwrap(),
+            MultivariateGaussian::new(arr1(&[4.0, 3.0]), arr2(&[[0.5, 0.0], [0.0, 2.0]])).unwrap(),
+        ],
+    )
+    .unwrap();
+    let mut rng = Rng::new(0);
+    let (x, _) = truth.sample_n(&mut rng, 1000);
+    for k in 1..=4 {
+        let fit = GaussianMixture::fit(&x, k, CovarianceType::Full, &mut rng).unwrap();
+        println!("k = {}: bic {} aic {}", k, fit.dist.bic(&x), fit.dist.aic(&x));
+    }
+    let fit = GaussianMixture::fit(&x, 2, CovarianceType::Full, &mut rng).unwrap();
+    println!("{}", fit.dist.weights());
+    for c in fit.dist.components() {
+        println!("{} {}", c.mean(), c.cov());
+    }
+    println!("{}", fit.dist.predict_proba(&x.slice(s![..5, ..]).to_owned()));
+
+    let prior = MixturePrior::from_data(&x).unwrap();
+    let vb = BayesianGaussianMixture::fit(&x, 8, &prior, &mut rng).unwrap();
+    println!("{} components after pruning, converged {}", vb.n_components(), vb.converged());
+    for (i, elbo) in vb.elbo().iter().enumerate().step_by(10) {
+        println!("iteration {}: elbo {}", i, elbo);
+    }
+    println!("{}", vb.weights());
+    println!("{}", vb.m());
+}
@@ -0,0 +1,20 @@
+use ndarray::*;
+use scicompt::mcmc::*;
+use scicompt::random::Rng;
+
+fn main() {
+    // Correlated Gaussian with precision matrix p
+    let p = arr2(&[[5.26, -2.37], [-2.37, 1.32]]);
+    let ln_p = |x: &Array1<f64>| -0.5 * x.dot(&p.dot(x));
+    let grad = |x: &Array1<f64>| -p.dot(x);
+    let x0 = arr1(&[3.0, -3.0]);
+    let mut rng = Rng::new(0);
+    let chains: Vec<_> = (0..4).map(|_| Nuts::new(1000).sample(&ln_p, &grad, &x0, 2000, &mut rng)).collect();
+    for c in chains.iter() {
+        println!("{} {}", c.acceptance_rate, c.mean());
+    }
+    println!("{}", effective_sample_size(&chains));
+    println!("{}", r_hat(&chains));
+    let c = Metropolis::new(2000).sample(&ln_p, &x0, 2000, &mut rng);
+    println!("{} {} {}", c.acceptance_rate, c.mean(), c.ess());
+}
@@ -0,0 +1,24 @@
+use ndarray::*;
+use scicompt::stats::*;
+
+fn main() {
+    let x = arr2(&[
+        [7.0, 4.0, 3.0],
+        [4.0, 1.0, 8.0],
+        [6.0, 3.0, 5.0],
+        [8.0, 6.0, 1.0],
+        [8.0, 5.0, 7.0],
+        [7.0, 2.0, 9.0],
+    ]);
+    let w = arr1(&[1.0, 2.0, 0.5, 1.0, 3.0, 1.0]);
+    println!("{}", mean(&x));
+    println!("{}", var(&x, 1));
+    println!("{}", median(&x));
+    println!("{}", quantile(&x, 0.25, Interpolation::Nearest));
+    println!("{}", mad(&x));
+    println!("{}", cov(&x, 1));
+    println!("{}", corr(&x));
+    println!("{}", weighted_mean(&x, &w));
+    println!("{}", weighted_cov(&x, &w, 0));
+    println!("{}", weighted_median(&x, &w));
+}
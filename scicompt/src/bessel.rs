@@ -0,0 +1,465 @@
+use crate::consts::FloatConst;
+
+/// Sum of a Chebyshev series on [-1, 1] (Clenshaw recurrence).
+fn chebyshev<T: FloatConst>(c: &[T], t: T) -> T {
+    let two_t = t + t;
+    let mut b1 = T::zero();
+    let mut b2 = T::zero();
+    for &ck in c[1..].iter().rev() {
+        let b0 = ck + two_t * b1 - b2;
+        b2 = b1;
+        b1 = b0;
+    }
+    c[0] + t * b1 - b2
+}
+
+/// Exponentially scaled modified Bessel function of the first kind of order 0, exp(-|x|) * I0(x).
+pub fn bessel_i0e<T: FloatConst>(x: T) -> T {
+    let x = x.abs();
+    let c = |v: f64| T::from(v).unwrap();
+    if x <= c(8.0) {
+        chebyshev(T::BESSEL_I0_A, x / c(4.0) - T::one())
+    } else {
+        chebyshev(T::BESSEL_I0_B, c(16.0) / x - T::one()) / x.sqrt()
+    }
+}
+
+/// Modified Bessel function of the first kind of order 0.
+pub fn bessel_i0<T: FloatConst>(x: T) -> T {
+    bessel_i0e(x) * x.abs().exp()
+}
+
+/// Exponentially scaled modified Bessel function of the first kind of order 1, exp(-|x|) * I1(x).
+pub fn bessel_i1e<T: FloatConst>(x: T) -> T {
+    let ax = x.abs();
+    let c = |v: f64| T::from(v).unwrap();
+    let v = if ax <= c(8.0) {
+        chebyshev(T::BESSEL_I1_A, ax / c(4.0) - T::one()) * ax
+    } else {
+        chebyshev(T::BESSEL_I1_B, c(16.0) / ax - T::one()) / ax.sqrt()
+    };
+    if x < T::zero() {
+        -v
+    } else {
+        v
+    }
+}
+
+/// Modified Bessel function of the first kind of order 1.
+pub fn bessel_i1<T: FloatConst>(x: T) -> T {
+    bessel_i1e(x) * x.abs().exp()
+}
+
+/// I_n(x) / I_0(x) by Miller's backward recurrence.
+fn bessel_in_ratio<T: FloatConst>(n: usize, x: T) -> T {
+    let big = T::from(1e10).unwrap();
+    let ax = x.abs();
+    let tox = T::from(2).unwrap() / ax;
+    // Start far enough above both n and x for the recurrence to forget its arbitrary initial values.
+    let start = n.max(ax.to_usize().unwrap_or(0));
+    let m = 2 * (start + (200.0 * start as f64).sqrt() as usize) + 10;
+    let mut bip = T::zero();
+    let mut bi = T::one();
+    let mut ans = T::zero();
+    for j in (1..=m).rev() {
+        let bim = bip + T::from(j).unwrap() * tox * bi;
+        bip = bi;
+        bi = bim;
+        if bi.abs() > big {
+            ans /= big;
+            bi /= big;
+            bip /= big;
+        }
+        if j == n {
+            ans = bip;
+        }
+    }
+    ans /= bi;
+    if x < T::zero() && n % 2 == 1 {
+        -ans
+    } else {
+        ans
+    }
+}
+
+/// Modified Bessel function of the first kind of integer order `n`.
+pub fn bessel_in<T: FloatConst>(n: usize, x: T) -> T {
+    match n {
+        0 => bessel_i0(x),
+        1 => bessel_i1(x),
+        _ if x == T::zero() => T::zero(),
+        _ => bessel_in_ratio(n, x) * bessel_i0(x),
+    }
+}
+
+/// Exponentially scaled modified Bessel function of the first kind of integer order `n`.
+pub fn bessel_ine<T: FloatConst>(n: usize, x: T) -> T {
+    match n {
+        0 => bessel_i0e(x),
+        1 => bessel_i1e(x),
+        _ if x == T::zero() => T::zero(),
+        _ => bessel_in_ratio(n, x) * bessel_i0e(x),
+    }
+}
+
+/// Modified Bessel function of the second kind of order 0, for x > 0.
+pub fn bessel_k0<T: FloatConst>(x: T) -> T {
+    let two = T::from(2).unwrap();
+    if x <= T::zero() {
+        return if x == T::zero() { T::infinity() } else { T::nan() };
+    }
+    if x <= two {
+        chebyshev(T::BESSEL_K0_A, x * x / two - T::one()) - (x / two).ln() * bessel_i0(x)
+    } else {
+        bessel_k0e(x) * (-x).exp()
+    }
+}
+
+/// Exponentially scaled modified Bessel function of the second kind of order 0, exp(x) * K0(x).
+pub fn bessel_k0e<T: FloatConst>(x: T) -> T {
+    let two = T::from(2).unwrap();
+    if x <= two {
+        bessel_k0(x) * x.exp()
+    } else {
+        chebyshev(T::BESSEL_K0_B, T::from(4).unwrap() / x - T::one()) / x.sqrt()
+    }
+}
+
+/// Modified Bessel function of the second kind of order 1, for x > 0.
+pub fn bessel_k1<T: FloatConst>(x: T) -> T {
+    let two = T::from(2).unwrap();
+    if x <= T::zero() {
+        return if x == T::zero() { T::infinity() } else { T::nan() };
+    }
+    if x <= two {
+        (x / two).ln() * bessel_i1(x) + chebyshev(T::BESSEL_K1_A, x * x / two - T::one()) / x
+    } else {
+        bessel_k1e(x) * (-x).exp()
+    }
+}
+
+/// Exponentially scaled modified Bessel function of the second kind of order 1, exp(x) * K1(x).
+pub fn bessel_k1e<T: FloatConst>(x: T) -> T {
+    let two = T::from(2).unwrap();
+    if x <= two {
+        bessel_k1(x) * x.exp()
+    } else {
+        chebyshev(T::BESSEL_K1_B, T::from(4).unwrap() / x - T::one()) / x.sqrt()
+    }
+}
+
+/// Upward recurrence K_(j+1) = K_(j-1) + 2j / x * K_j from K_0 and K_1, which is stable for K.
+fn bessel_kn_recur<T: FloatConst>(n: usize, x: T, k0: T, k1: T) -> T {
+    if n == 0 {
+        return k0;
+    }
+    let tox = T::from(2).unwrap() / x;
+    let mut bkm = k0;
+    let mut bk = k1;
+    for j in 1..n {
+        let bkp = bkm + T::from(j).unwrap() * tox * bk;
+        bkm = bk;
+        bk = bkp;
+    }
+    bk
+}
+
+/// Modified Bessel function of the second kind of integer order `n`, for x > 0.
+pub fn bessel_kn<T: FloatConst>(n: usize, x: T) -> T {
+    bessel_kn_recur(n, x, bessel_k0(x), bessel_k1(x))
+}
+
+/// Exponentially scaled modified Bessel function of the second kind of integer order `n`.
+pub fn bessel_kne<T: FloatConst>(n: usize, x: T) -> T {
+    bessel_kn_recur(n, x, bessel_k0e(x), bessel_k1e(x))
+}
+
+/// K_nu(x) for real order (Temme's series for x < 2, Steed's continued fraction otherwise),
+/// multiplied by exp(x) if `scaled`.
+fn bessel_kv_impl<T: FloatConst>(nu: T, x: T, scaled: bool) -> T {
+    let one = T::one();
+    let two = T::from(2).unwrap();
+    let half = T::from(0.5).unwrap();
+    let eps = T::epsilon();
+    let pi = T::from(std::f64::consts::PI).unwrap();
+    if x <= T::zero() {
+        return if x == T::zero() { T::infinity() } else { T::nan() };
+    }
+    let nu = nu.abs();
+    let nl = (nu + half).floor();
+    let mu = nu - nl;
+    let mu2 = mu * mu;
+    let xi = one / x;
+    let xi2 = two * xi;
+    let (mut k_mu, mut k_mu1) = if x < two {
+        let x2 = half * x;
+        let pimu = pi * mu;
+        let fact = if pimu.abs() < eps { one } else { pimu / pimu.sin() };
+        let d = -x2.ln();
+        let e = mu * d;
+        let fact2 = if e.abs() < eps { one } else { e.sinh() / e };
+        // gam1 = (1 / G(1 - mu) - 1 / G(1 + mu)) / 2mu and gam2 = (1 / G(1 - mu) + 1 / G(1 + mu)) / 2.
+        let s = T::from(8).unwrap() * mu2 - one;
+        let gam1 = chebyshev(T::BESSEL_GAM1, s);
+        let gam2 = chebyshev(T::BESSEL_GAM2, s);
+        let gampl = gam2 - mu * gam1;
+        let gammi = gam2 + mu * gam1;
+        let mut ff = fact * (gam1 * e.cosh() + gam2 * fact2 * d);
+        let mut sum = ff;
+        let e = e.exp();
+        let mut p = half * e / gampl;
+        let mut q = half / (e * gammi);
+        let mut c = one;
+        let d = x2 * x2;
+        let mut sum1 = p;
+        for i in 1..10000 {
+            let i = T::from(i).unwrap();
+            ff = (i * ff + p + q) / (i * i - mu2);
+            c *= d / i;
+            p /= i - mu;
+            q /= i + mu;
+            let del = c * ff;
+            sum += del;
+            sum1 += c * (p - i * ff);
+            if del.abs() < sum.abs() * eps {
+                break;
+            }
+        }
+        let scale = if scaled { x.exp() } else { one };
+        (sum * scale, sum1 * xi2 * scale)
+    } else {
+        let mut b = two * (one + x);
+        let mut d = one / b;
+        let mut delh = d;
+        let mut h = d;
+        let mut q1 = T::zero();
+        let mut q2 = one;
+        let a1 = T::from(0.25).unwrap() - mu2;
+        let mut q = a1;
+        let mut c = a1;
+        let mut a = -a1;
+        let mut s = one + q * delh;
+        for i in 1..10000 {
+            let fi = T::from(i).unwrap();
+            a -= two * fi;
+            c = -a * c / (fi + one);
+            let qnew = (q1 - b * q2) / a;
+            q1 = q2;
+            q2 = qnew;
+            q += c * qnew;
+            b += two;
+            d = one / (b + a * d);
+            delh = (b * d - one) * delh;
+            h += delh;
+            let dels = q * delh;
+            s += dels;
+            if (dels / s).abs() < eps {
+                break;
+            }
+        }
+        h = a1 * h;
+        let scale = if scaled { one } else { (-x).exp() };
+        let k_mu = (pi / (two * x)).sqrt() * scale / s;
+        (k_mu, k_mu * (mu + x + half - h) * xi)
+    };
+    let mut order = mu;
+    while order + half < nu {
+        order += one;
+        let k = order * xi2 * k_mu1 + k_mu;
+        k_mu = k_mu1;
+        k_mu1 = k;
+    }
+    k_mu
+}
+
+/// Modified Bessel function of the second kind of real order `nu`, for x > 0.
+pub fn bessel_kv<T: FloatConst>(nu: T, x: T) -> T {
+    bessel_kv_impl(nu, x, false)
+}
+
+/// Exponentially scaled modified Bessel function of the second kind of real order `nu`, exp(x) * K_nu(x).
+pub fn bessel_kve<T: FloatConst>(nu: T, x: T) -> T {
+    bessel_kv_impl(nu, x, true)
+}
+
+/// Hankel asymptotic factors P(x) and Q(x) for x > 8, from Chebyshev series in 128 / x^2 - 1.
+fn bessel_pq<T: FloatConst>(p: &[T], q: &[T], x: T) -> (T, T) {
+    let t = T::from(128).unwrap() / (x * x) - T::one();
+    (chebyshev(p, t), chebyshev(q, t) / x)
+}
+
+/// Bessel function of the first kind of order 0.
+pub fn bessel_j0<T: FloatConst>(x: T) -> T {
+    let x = x.abs();
+    let c = |v: f64| T::from(v).unwrap();
+    if x <= c(8.0) {
+        // Factor out the first two zeros so the result keeps its relative accuracy near them.
+        let z = T::BESSEL_ZEROS;
+        let f = (x - z[0] - z[1]) * (x + z[0]) * (x - z[2] - z[3]) * (x + z[2]);
+        return f * chebyshev(T::BESSEL_J0_A, x * x / c(32.0) - T::one());
+    }
+    let (p, q) = bessel_pq(T::BESSEL_P0, T::BESSEL_Q0, x);
+    let (s, co) = x.sin_cos();
+    (p * (co + s) - q * (s - co)) / (T::from(std::f64::consts::PI).unwrap() * x).sqrt()
+}
+
+/// Bessel function of the first kind of order 1.
+pub fn bessel_j1<T: FloatConst>(x: T) -> T {
+    let ax = x.abs();
+    let c = |v: f64| T::from(v).unwrap();
+    let v = if ax <= c(8.0) {
+        let z = T::BESSEL_ZEROS;
+        let f = ax * (ax - z[4] - z[5]) * (ax + z[4]) * (ax - z[6] - z[7]) * (ax + z[6]);
+        f * chebyshev(T::BESSEL_J1_A, ax * ax / c(32.0) - T::one())
+    } else {
+        let (p, q) = bessel_pq(T::BESSEL_P1, T::BESSEL_Q1, ax);
+        let (s, co) = ax.sin_cos();
+        (p * (s - co) + q * (s + co)) / (T::from(std::f64::consts::PI).unwrap() * ax).sqrt()
+    };
+    if x < T::zero() {
+        -v
+    } else {
+        v
+    }
+}
+
+/// Bessel function of the second kind of order 0, for x > 0.
+pub fn bessel_y0<T: FloatConst>(x: T) -> T {
+    let c = |v: f64| T::from(v).unwrap();
+    if x <= T::zero() {
+        return if x == T::zero() { T::neg_infinity() } else { T::nan() };
+    }
+    if x <= c(8.0) {
+        let frac_2_pi = c(std::f64::consts::FRAC_2_PI);
+        return chebyshev(T::BESSEL_Y0_A, x * x / c(32.0) - T::one()) + frac_2_pi * (x / c(2.0)).ln() * bessel_j0(x);
+    }
+    let (p, q) = bessel_pq(T::BESSEL_P0, T::BESSEL_Q0, x);
+    let (s, co) = x.sin_cos();
+    (p * (s - co) + q * (s + co)) / (T::from(std::f64::consts::PI).unwrap() * x).sqrt()
+}
+
+/// Bessel function of the second kind of order 1, for x > 0.
+pub fn bessel_y1<T: FloatConst>(x: T) -> T {
+    let c = |v: f64| T::from(v).unwrap();
+    if x <= T::zero() {
+        return if x == T::zero() { T::neg_infinity() } else { T::nan() };
+    }
+    if x <= c(8.0) {
+        let frac_2_pi = c(std::f64::consts::FRAC_2_PI);
+        return x * chebyshev(T::BESSEL_Y1_A, x * x / c(32.0) - T::one())
+            + frac_2_pi * ((x / c(2.0)).ln() * bessel_j1(x) - x.recip());
+    }
+    let (p, q) = bessel_pq(T::BESSEL_P1, T::BESSEL_Q1, x);
+    let (s, co) = x.sin_cos();
+    (q * (s - co) - p * (s + co)) / (T::from(std::f64::consts::PI).unwrap() * x).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::assert_close;
+
+    #[test]
+    fn modified_first_kind() {
+        // (x, I0(x), I1(x), I0e(x), I1e(x))
+        let cases = [
+            (1e-3, 1.0000002500000156, 0.0005000000625000026, 0.9990007495835156, 0.0004995003123542213),
+            (0.5, 1.0634833707413236, 0.2578943053908963, 0.6450352704491501, 0.1564208031848717),
+            (2.0, 2.2795853023360673, 1.590636854637329, 0.30850832255367105, 0.21526928924893765),
+            (-3.5, 7.3782034322254795, -6.205834922258365, 0.22280243801077917, -0.18739997660305),
+            (8.0, 427.5641157218048, 399.8731367825601, 0.14343178185685032, 0.13414249329269817),
+            (30.0, 781672297823.9775, 768532038938.957, 0.0731459464822373, 0.07191633059864755),
+            (700.0, 1.5295933476718737e302, 1.5285003902339006e302, 0.015081295651531358, 0.015070519444716848),
+        ];
+        for &(x, i0, i1, i0e, i1e) in cases.iter() {
+            assert_close(bessel_i0(x), i0, 1e-14);
+            assert_close(bessel_i1(x), i1, 1e-14);
+            assert_close(bessel_i0e(x), i0e, 1e-14);
+            assert_close(bessel_i1e(x), i1e, 1e-14);
+        }
+        // (n, x, In(x), Ine(x))
+        let cases = [
+            (2, 0.5, 0.031906149177738256, 0.01935205770966328),
+            (3, 4.0, 3.337275778420344, 0.06112433802966629),
+            (5, 20.0, 23018392.21341367, 0.04744444249338908),
+            (10, 1.0, 2.7529480398368737e-10, 1.0127529864692066e-10),
+            (4, -2.5, 0.13797716675187888, 0.011325855542957432),
+        ];
+        for &(n, x, i, ie) in cases.iter() {
+            assert_close(bessel_in(n, x), i, 1e-13);
+            assert_close(bessel_ine(n, x), ie, 1e-13);
+        }
+        assert_eq!(bessel_in(0, 2.0), bessel_i0(2.0));
+        assert_close(bessel_i0(0.0), 1.0, 1e-15);
+        assert_eq!(bessel_i1(0.0), 0.0);
+    }
+
+    #[test]
+    fn modified_second_kind() {
+        // (x, K0(x), K1(x), K0e(x), K1e(x))
+        let cases = [
+            (1e-3, 7.023688800562382, 999.9962381560856, 7.030716002378251, 1000.9967345590684),
+            (0.5, 0.9244190712276659, 1.656441120003301, 1.5241093857739094, 2.731009708211786),
+            (2.0, 0.11389387274953344, 0.13986588181652243, 0.8415682150707714, 1.0334768470686886),
+            (8.0, 0.0001464707052228154, 0.00015536921180500115, 0.4366230186015861, 0.4631490928704961),
+            (30.0, 2.1324774964630563e-14, 2.1677320018915495e-14, 0.22788666561625373, 0.2316541293777118),
+            (700.0, 4.669776431685377e-306, 4.6731107967079664e-306, 0.04736236945461357, 0.04739618765349454),
+        ];
+        for &(x, k0, k1, k0e, k1e) in cases.iter() {
+            assert_close(bessel_k0(x), k0, 1e-14);
+            assert_close(bessel_k1(x), k1, 1e-14);
+            assert_close(bessel_k0e(x), k0e, 1e-14);
+            assert_close(bessel_k1e(x), k1e, 1e-14);
+        }
+        // (n, x, Kn(x), Kne(x))
+        let cases = [
+            (2, 0.5, 7.5501835512408695, 12.448148218621052),
+            (3, 4.0, 0.029884924416755672, 1.6316615870352018),
+            (5, 20.0, 1.0538660139974233e-9, 0.5112991106167967),
+        ];
+        for &(n, x, k, ke) in cases.iter() {
+            assert_close(bessel_kn(n, x), k, 1e-14);
+            assert_close(bessel_kne(n, x), ke, 1e-14);
+        }
+        // (nu, x, Kv(x), Kve(x))
+        let cases = [
+            (0.3, 0.2, 1.934603404494532, 2.362929934195676),
+            (1.5, 1.0, 0.9221370088957891, 2.5066282746310007),
+            (2.7, 3.0, 0.0969221537279902, 1.9467334973784185),
+            (0.5, 10.0, 1.799347809370518e-5, 0.3963327297606011),
+            (10.25, 5.0, 13.901454760652625, 2063.158817150009),
+        ];
+        for &(nu, x, k, ke) in cases.iter() {
+            assert_close(bessel_kv(nu, x), k, 1e-13);
+            assert_close(bessel_kve(nu, x), ke, 1e-13);
+        }
+        // K_(n + 1/2) has a closed form; K_(1/2)(x) = sqrt(pi / 2x) exp(-x).
+        let x = 3.0f64;
+        assert_close(bessel_kv(0.5, x), (std::f64::consts::PI / (2.0 * x)).sqrt() * (-x).exp(), 1e-14);
+        assert_close(bessel_kv(-2.7, 3.0), 0.0969221537279902, 1e-13);
+    }
+
+    #[test]
+    fn first_and_second_kind() {
+        // (x, J0(x), J1(x), Y0(x), Y1(x))
+        let cases = [
+            (1e-3, 0.9999997500000156, 0.0004999999375000026, -4.471416611375923, -636.6221672311394),
+            (0.5, 0.9384698072408129, 0.2422684576748739, -0.44451873350670656, -1.471472392670243),
+            (2.0, 0.22389077914123567, 0.5767248077568734, 0.5103756726497451, -0.10703243154093754),
+            (7.9, 0.19436184484127825, 0.2191793999217512, 0.20652094814437577, -0.18172107728057313),
+            (8.1, 0.14751745404437766, 0.24760776698159287, 0.23809132870223482, -0.13314879595249593),
+            (30.0, -0.08636798358104021, -0.11875106261662294, -0.11729573168666403, 0.08442557066174723),
+            (1000.0, 0.024786686152420176, 0.004728311907089524, 0.0047159179776228135, -0.024784331292351778),
+        ];
+        for &(x, j0, j1, y0, y1) in cases.iter() {
+            assert_close(bessel_j0(x), j0, 1e-13);
+            assert_close(bessel_j1(x), j1, 1e-13);
+            assert_close(bessel_y0(x), y0, 1e-13);
+            assert_close(bessel_y1(x), y1, 1e-13);
+        }
+        assert_close(bessel_j0(-3.5), -0.3801277399872634, 1e-14);
+        assert_close(bessel_j1(-3.5), -0.1373775273623272, 1e-14);
+        assert_close(bessel_j0(0.0), 1.0, 1e-15);
+        assert_eq!(bessel_j1(0.0), 0.0);
+    }
+}
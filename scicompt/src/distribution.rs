@@ -1,16 +1,391 @@
+use ndarray::*;
+use ndarray_linalg::*;
+use std::fmt;
+
+use crate::bessel::{bessel_i0e, bessel_i1e, bessel_ine};
+use crate::beta::{beta_inc, beta_inc_inv, ln_beta};
+use crate::consts::FloatConst;
+use crate::erf::{erfc, erfcinv};
 use crate::float::Float;
+use crate::gamma::{digamma, gamma_p, gamma_p_inv, gamma_q, gamma_q_inv, ln_gamma, ln_gamma_multi, trigamma};
+use crate::integer::Integer;
+use crate::kmeans::kmeans;
+use crate::random::Rng;
 
 pub trait Distribution<T: Float> {
+    /// The mean direction for `Circular` distributions.
     fn mean(&self) -> T;
+    /// The circular variance 1 - R for `Circular` distributions.
     fn var(&self) -> T;
     fn std(&self) -> T {
         self.var().sqrt()
     }
-    fn mode(&self) -> T;
+    /// `None` if the mode is undefined or not unique.
+    fn mode(&self) -> Option<T>;
+    /// Differential entropy for continuous distributions, in nats.
+    fn entropy(&self) -> T;
+    /// NaN where the third moment is undefined.
+    fn skewness(&self) -> T;
+    /// Excess kurtosis, zero for the Gaussian. NaN where the fourth moment is undefined.
+    fn kurtosis(&self) -> T;
+    /// Probability density at `x` (probability mass for discrete distributions).
+    fn pdf(&self, x: T) -> T {
+        self.ln_pdf(x).exp()
+    }
+    fn ln_pdf(&self, x: T) -> T;
+    fn cdf(&self, x: T) -> T;
+    /// Survival function 1 - cdf(x). Overridden where the upper tail can be computed without cancellation.
+    fn sf(&self, x: T) -> T {
+        T::one() - self.cdf(x)
+    }
+    /// Inverse of the CDF: the smallest `x` with `cdf(x) >= p`.
+    fn quantile(&self, p: T) -> T;
+    /// Inverse of the survival function, quantile(1 - p), keeping full precision for small `p` where overridden.
+    fn isf(&self, p: T) -> T {
+        self.quantile(T::one() - p)
+    }
+    fn sample(&self, rng: &mut Rng) -> T;
+    fn sample_n(&self, rng: &mut Rng, n: usize) -> Array1<T> {
+        (0..n).map(|_| self.sample(rng)).collect()
+    }
+    fn ln_likelihood(&self, x: &Array1<T>) -> T {
+        x.iter().map(|&xi| self.ln_pdf(xi)).sum()
+    }
+}
+
+/// Distributions over vectors or matrices.
+///
+/// `var` is the element-wise variance; vector-valued distributions also provide the full covariance as `cov`.
+pub trait MultivariateDistribution<T: Float> {
+    type Value;
+    fn mean(&self) -> Self::Value;
+    fn var(&self) -> Self::Value;
+    /// `None` if the density has no maximum.
+    fn mode(&self) -> Option<Self::Value>;
+    fn pdf(&self, x: &Self::Value) -> T {
+        self.ln_pdf(x).exp()
+    }
+    fn ln_pdf(&self, x: &Self::Value) -> T;
+    fn sample(&self, rng: &mut Rng) -> Self::Value;
+}
+
+/// Exponential family in canonical form, p(x) = h(x) exp(eta . t(x) - A(eta)).
+///
+/// `eta` are the natural parameters, `t` the sufficient statistics and `A` the log-partition function.
+/// The mean parameters are the expected sufficient statistics, E[t(x)] = grad A(eta).
+pub trait ExponentialFamily<T: Float>: Distribution<T> + Sized {
+    fn natural_params(&self) -> Array1<T>;
+    fn from_natural_params(eta: &Array1<T>) -> Result<Self, ParamError>;
+    fn sufficient_stats(x: T) -> Array1<T>;
+    /// A(eta)
+    fn ln_partition(eta: &Array1<T>) -> T;
+    /// ln h(x)
+    fn ln_base_measure(x: T) -> T;
+    fn mean_params(&self) -> Array1<T>;
+    fn from_mean_params(m: &Array1<T>) -> Result<Self, ParamError>;
+}
+
+/// Distributions supported on the integers.
+///
+/// `Distribution::pdf` of these types is the probability mass at `x`, and zero at non-integers.
+pub trait Discrete<T: Float>: Distribution<T> {
+    fn pmf<S: Integer>(&self, k: S) -> T {
+        self.ln_pmf(k).exp()
+    }
+    fn ln_pmf<S: Integer>(&self, k: S) -> T;
+    fn sample_int<S: Integer>(&self, rng: &mut Rng) -> S {
+        S::from(self.sample(rng)).unwrap()
+    }
+}
+
+/// Distributions of angles, with support [-pi, pi).
+///
+/// `Distribution::mean` is the mean direction and `var` the circular variance 1 - R, where R is the mean
+/// resultant length. `std` is the circular standard deviation sqrt(-2 ln R), `skewness` and `kurtosis` are
+/// Mardia's circular coefficients, and the density is periodic in `x`.
+pub trait Circular<T: Float>: Distribution<T> {
+    /// |E[exp(i p (x - mean))]|, the `p`-th trigonometric moment about the mean direction.
+    fn trig_moment(&self, p: usize) -> T;
+    fn mean_resultant_length(&self) -> T {
+        self.trig_moment(1)
+    }
+}
+
+/// `x` moved into [-pi, pi) by a multiple of 2 pi.
+fn wrap_angle<T: Float>(x: T) -> T {
+    let pi = T::from(std::f64::consts::PI).unwrap();
+    let two_pi = pi + pi;
+    let y = x - two_pi * ((x + pi) / two_pi).floor();
+    if y >= pi {
+        y - two_pi
+    } else {
+        y
+    }
+}
+
+/// Mean direction and mean resultant length of the angles `x`.
+fn mean_direction<T: Float>(x: &Array1<T>) -> (T, T) {
+    let c = x.map(|&xi| xi.cos()).mean().unwrap();
+    let s = x.map(|&xi| xi.sin()).mean().unwrap();
+    (s.atan2(c), c.hypot(s))
+}
+
+/// CDF on [-pi, pi) of a circular density with mean direction `mu` and trigonometric moments `rho[n - 1]`.
+fn circular_cdf_series<T: Float>(x: T, mu: T, rho: &[T]) -> T {
+    let pi = T::from(std::f64::consts::PI).unwrap();
+    let mut sum = T::zero();
+    for (i, &r) in rho.iter().enumerate() {
+        let n = T::from(i + 1).unwrap();
+        // The second term subtracts the series at x = -pi.
+        let s0 = if i % 2 == 0 { -(n * mu).sin() } else { (n * mu).sin() };
+        sum += r / n * ((n * (x - mu)).sin() + s0);
+    }
+    ((x + pi) / (pi + pi) + sum / pi).max(T::zero()).min(T::one())
+}
+
+/// Inverts the CDF of a circular distribution by Newton's method, safeguarded by bisection on [-pi, pi].
+fn circular_quantile<T: Float, D: Distribution<T>>(dist: &D, p: T) -> T {
+    let pi = T::from(std::f64::consts::PI).unwrap();
+    if p <= T::zero() {
+        return -pi;
+    }
+    if p >= T::one() {
+        return pi;
+    }
+    let (mut lo, mut hi) = (-pi, pi);
+    let mut x = dist.mean();
+    for _ in 0..100 {
+        let err = dist.cdf(x) - p;
+        if err < T::zero() {
+            lo = x;
+        } else {
+            hi = x;
+        }
+        let mut next = x - err / dist.pdf(x);
+        if !(next > lo && next < hi) {
+            next = T::from(0.5).unwrap() * (lo + hi);
+        }
+        let dx = next - x;
+        x = next;
+        if dx.abs() <= T::epsilon() * (T::one() + x.abs()) {
+            break;
+        }
+    }
+    x
+}
+
+fn discrete_ln_pdf<T: Float, D: Discrete<T>>(dist: &D, x: T) -> T {
+    match x.to_i64() {
+        Some(k) if x.fract() == T::zero() => dist.ln_pmf(k),
+        _ => T::neg_infinity(),
+    }
+}
+
+/// Smallest integer `k` in [`lo`, `hi`] with `cdf(k) >= p`, found by doubling and then bisection.
+fn discrete_quantile<T: Float, F: Fn(T) -> T>(p: T, lo: T, hi: T, cdf: F) -> T {
+    let one = T::one();
+    if p >= one {
+        return hi;
+    }
+    if cdf(lo) >= p {
+        return lo;
+    }
+    let mut a = lo;
+    let mut step = one;
+    let mut b = lo + step;
+    while b < hi && cdf(b) < p {
+        a = b;
+        step = step + step;
+        b = lo + step;
+    }
+    if b > hi {
+        b = hi;
+    }
+    while b - a > one {
+        let m = ((a + b) / T::from(2).unwrap()).floor();
+        if cdf(m) >= p {
+            b = m;
+        } else {
+            a = m;
+        }
+    }
+    b
+}
+
+/// Entropy of a discrete distribution by summing over `k` in [`lo`, `hi`].
+fn discrete_entropy<T: Float, D: Discrete<T>>(dist: &D, lo: usize, hi: usize) -> T {
+    (lo..=hi).map(|k| {
+        let ln_p = dist.ln_pmf(k);
+        if ln_p == T::neg_infinity() {
+            T::zero()
+        } else {
+            -ln_p.exp() * ln_p
+        }
+    }).sum()
+}
+
+/// x * ln(y), taken to be zero when x is zero.
+fn xlny<T: Float>(x: T, y: T) -> T {
+    if x == T::zero() {
+        T::zero()
+    } else {
+        x * y.ln()
+    }
+}
+
+/// x * ln(1 + y), taken to be zero when x is zero.
+fn xlog1py<T: Float>(x: T, y: T) -> T {
+    if x == T::zero() {
+        T::zero()
+    } else {
+        x * y.ln_1p()
+    }
+}
+
+/// Invalid parameter passed to a distribution constructor, or data a distribution cannot be fitted to. The payload
+/// names the parameter.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ParamError {
+    NotFinite(&'static str),
+    NotPositive(&'static str),
+    NotProbability(&'static str),
+    /// Probabilities do not sum to one
+    NotNormalized(&'static str),
+    NotPositiveDefinite(&'static str),
+    /// Lower bound is not below the upper bound
+    EmptyRange,
+    ShapeMismatch,
+    OutOfRange(&'static str),
+    /// No observations to fit
+    EmptyData,
+}
+
+impl fmt::Display for ParamError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParamError::NotFinite(name) => write!(f, "{} must be finite", name),
+            ParamError::NotPositive(name) => write!(f, "{} must be positive", name),
+            ParamError::NotProbability(name) => write!(f, "{} must be in [0, 1]", name),
+            ParamError::NotNormalized(name) => write!(f, "{} must sum to one", name),
+            ParamError::NotPositiveDefinite(name) => write!(f, "{} must be positive definite", name),
+            ParamError::EmptyRange => write!(f, "lower bound must be below upper bound"),
+            ParamError::ShapeMismatch => write!(f, "parameter shapes do not match"),
+            ParamError::OutOfRange(name) => write!(f, "{} is out of range", name),
+            ParamError::EmptyData => write!(f, "data must not be empty"),
+        }
+    }
+}
+
+impl std::error::Error for ParamError {}
+
+fn check_nonempty<T>(x: &Array1<T>) -> Result<(), ParamError> {
+    if x.is_empty() {
+        Err(ParamError::EmptyData)
+    } else {
+        Ok(())
+    }
+}
+
+/// Checks that a parameter vector has `len` entries.
+fn check_len<T>(x: &Array1<T>, len: usize) -> Result<(), ParamError> {
+    if x.len() == len {
+        Ok(())
+    } else {
+        Err(ParamError::ShapeMismatch)
+    }
+}
+
+/// Checks that the observations are whole numbers in [lo, hi].
+fn check_counts<T: Float>(x: &Array1<T>, lo: T, hi: T) -> Result<(), ParamError> {
+    if x.iter().all(|&xi| xi.is_finite() && xi.floor() == xi && xi >= lo && xi <= hi) {
+        Ok(())
+    } else {
+        Err(ParamError::OutOfRange("x"))
+    }
+}
+
+fn check_finite<T: Float>(name: &'static str, x: T) -> Result<(), ParamError> {
+    if x.is_finite() {
+        Ok(())
+    } else {
+        Err(ParamError::NotFinite(name))
+    }
+}
+
+fn check_positive<T: Float>(name: &'static str, x: T) -> Result<(), ParamError> {
+    check_finite(name, x)?;
+    if x > T::zero() {
+        Ok(())
+    } else {
+        Err(ParamError::NotPositive(name))
+    }
+}
+
+fn check_probability<T: Float>(name: &'static str, x: T) -> Result<(), ParamError> {
+    if x >= T::zero() && x <= T::one() {
+        Ok(())
+    } else {
+        Err(ParamError::NotProbability(name))
+    }
+}
+
+fn check_simplex<T: Float>(name: &'static str, p: &Array1<T>) -> Result<(), ParamError> {
+    for &pi in p.iter() {
+        check_probability(name, pi)?;
+    }
+    let tol = T::epsilon().sqrt() * T::from(p.len()).unwrap();
+    if (p.sum() - T::one()).abs() <= tol {
+        Ok(())
+    } else {
+        Err(ParamError::NotNormalized(name))
+    }
+}
+
+/// Distribution estimated from data, with the log-likelihood of the data under it.
+pub struct FitResult<T: Float, D> {
+    pub dist: D,
+    pub ln_likelihood: T,
+    pub converged: bool,
+    pub n_iter: usize,
+}
+
+impl<T: Float, D: Distribution<T>> FitResult<T, D> {
+    fn new(dist: D, x: &Array1<T>, converged: bool, n_iter: usize) -> Self {
+        let ln_likelihood = dist.ln_likelihood(x);
+        Self { dist, ln_likelihood, converged, n_iter }
+    }
 }
 
 pub struct Bernoulli<T: Float> {
-    pub mu: T,
+    mu: T,
+}
+
+impl<T: Float> Bernoulli<T> {
+    pub fn new(mu: T) -> Result<Self, ParamError> {
+        check_probability("mu", mu)?;
+        Ok(Self { mu })
+    }
+
+    pub fn mu(&self) -> T {
+        self.mu
+    }
+
+    /// Maximum likelihood estimate from observations in {0, 1}.
+    pub fn fit(x: &Array1<T>) -> Result<FitResult<T, Self>, ParamError> {
+        check_nonempty(x)?;
+        if x.iter().any(|&xi| xi != T::zero() && xi != T::one()) {
+            return Err(ParamError::OutOfRange("x"));
+        }
+        let mu = x.mean().unwrap();
+        Ok(FitResult::new(Self::new(mu)?, x, true, 0))
+    }
+
+    /// KL(self || other)
+    pub fn kl_divergence(&self, other: &Self) -> T {
+        let q1 = T::one() - self.mu;
+        let q2 = T::one() - other.mu;
+        xlny(self.mu, self.mu / other.mu) + xlny(q1, q1 / q2)
+    }
 }
 
 impl<T: Float> Distribution<T> for Bernoulli<T> {
@@ -22,8 +397,66 @@ impl<T: Float> Distribution<T> for Bernoulli<T> {
         self.mu * (T::one() - self.mu)
     }
 
-    fn mode(&self) -> T {
-        if self.mu >= T::from(0.5).unwrap() {
+    fn mode(&self) -> Option<T> {
+        let half = T::from(0.5).unwrap();
+        if self.mu > half {
+            Some(T::one())
+        } else if self.mu < half {
+            Some(T::zero())
+        } else {
+            None
+        }
+    }
+
+    fn entropy(&self) -> T {
+        let q = T::one() - self.mu;
+        -xlny(self.mu, self.mu) - xlny(q, q)
+    }
+
+    fn skewness(&self) -> T {
+        let q = T::one() - self.mu;
+        (q - self.mu) / (self.mu * q).sqrt()
+    }
+
+    fn kurtosis(&self) -> T {
+        let pq = self.mu * (T::one() - self.mu);
+        (T::one() - T::from(6).unwrap() * pq) / pq
+    }
+
+    fn pdf(&self, x: T) -> T {
+        if x == T::one() {
+            self.mu
+        } else if x == T::zero() {
+            T::one() - self.mu
+        } else {
+            T::zero()
+        }
+    }
+
+    fn ln_pdf(&self, x: T) -> T {
+        self.pdf(x).ln()
+    }
+
+    fn cdf(&self, x: T) -> T {
+        if x < T::zero() {
+            T::zero()
+        } else if x < T::one() {
+            T::one() - self.mu
+        } else {
+            T::one()
+        }
+    }
+
+    fn quantile(&self, p: T) -> T {
+        if p <= T::one() - self.mu {
+            T::zero()
+        } else {
+            T::one()
+        }
+    }
+
+    fn sample(&self, rng: &mut Rng) -> T {
+        if rng.uniform::<T>() < self.mu {
             T::one()
         } else {
             T::zero()
@@ -31,12 +464,157 @@ impl<T: Float> Distribution<T> for Bernoulli<T> {
     }
 }
 
+impl<T: Float> Discrete<T> for Bernoulli<T> {
+    fn ln_pmf<S: Integer>(&self, k: S) -> T {
+        if k == S::one() {
+            self.mu.ln()
+        } else if k == S::zero() {
+            (T::one() - self.mu).ln()
+        } else {
+            T::neg_infinity()
+        }
+    }
+}
+
+impl<T: Float> ExponentialFamily<T> for Bernoulli<T> {
+    fn natural_params(&self) -> Array1<T> {
+        arr1(&[(self.mu / (T::one() - self.mu)).ln()])
+    }
+
+    fn from_natural_params(eta: &Array1<T>) -> Result<Self, ParamError> {
+        check_len(eta, 1)?;
+        Self::new(T::one() / (T::one() + (-eta[0]).exp()))
+    }
+
+    fn sufficient_stats(x: T) -> Array1<T> {
+        arr1(&[x])
+    }
+
+    fn ln_partition(eta: &Array1<T>) -> T {
+        // ln(1 + exp(eta)), arranged not to overflow
+        let e = eta[0];
+        e.max(T::zero()) + (-e.abs()).exp().ln_1p()
+    }
+
+    fn ln_base_measure(x: T) -> T {
+        if x == T::zero() || x == T::one() {
+            T::zero()
+        } else {
+            T::neg_infinity()
+        }
+    }
+
+    fn mean_params(&self) -> Array1<T> {
+        arr1(&[self.mu])
+    }
+
+    fn from_mean_params(m: &Array1<T>) -> Result<Self, ParamError> {
+        check_len(m, 1)?;
+        Self::new(m[0])
+    }
+}
+
 pub struct Beta<T: Float> {
-    pub a: T,
-    pub b: T,
+    a: T,
+    b: T,
+}
+
+impl<T: FloatConst> Beta<T> {
+    pub fn new(a: T, b: T) -> Result<Self, ParamError> {
+        check_positive("a", a)?;
+        check_positive("b", b)?;
+        Ok(Self { a, b })
+    }
+
+    pub fn a(&self) -> T {
+        self.a
+    }
+
+    pub fn b(&self) -> T {
+        self.b
+    }
+
+    /// Method of moments estimate.
+    pub fn fit_moments(x: &Array1<T>) -> Result<Self, ParamError> {
+        check_nonempty(x)?;
+        let one = T::one();
+        let m = x.mean().unwrap();
+        let v = x.var_axis(Axis(0), T::zero()).into_scalar();
+        let common = m * (one - m) / v - one;
+        Self::new(m * common, (one - m) * common)
+    }
+
+    /// Posterior over the Bernoulli parameter after observing `x` in {0, 1}.
+    pub fn posterior(&self, x: &Array1<T>) -> Result<Self, ParamError> {
+        if x.iter().any(|&xi| xi != T::zero() && xi != T::one()) {
+            return Err(ParamError::OutOfRange("x"));
+        }
+        let n = T::from(x.len()).unwrap();
+        let m = x.sum();
+        Self::new(self.a + m, self.b + n - m)
+    }
+
+    /// Predictive distribution of the next observation.
+    pub fn posterior_predictive(&self) -> Bernoulli<T> {
+        Bernoulli { mu: self.a / (self.a + self.b) }
+    }
+
+    /// KL(self || other)
+    pub fn kl_divergence(&self, other: &Self) -> T {
+        let (a1, b1) = (self.a, self.b);
+        let (a2, b2) = (other.a, other.b);
+        ln_beta(a2, b2) - ln_beta(a1, b1) + (a1 - a2) * digamma(a1) + (b1 - b2) * digamma(b1)
+            + (a2 - a1 + b2 - b1) * digamma(a1 + b1)
+    }
+
+    /// Maximum likelihood estimate by Newton's method, starting from the method of moments. The observations must
+    /// lie strictly inside (0, 1).
+    pub fn fit(x: &Array1<T>) -> Result<FitResult<T, Self>, ParamError> {
+        check_nonempty(x)?;
+        if x.iter().any(|&xi| !(xi > T::zero() && xi < T::one())) {
+            return Err(ParamError::OutOfRange("x"));
+        }
+        let ln_x = x.map(|&xi| xi.ln()).mean().unwrap();
+        let ln_1mx = x.map(|&xi| (-xi).ln_1p()).mean().unwrap();
+        let (dist, converged, n_iter) = Self::solve_mean_ln(ln_x, ln_1mx, Self::fit_moments(x)?);
+        Ok(FitResult::new(dist, x, converged, n_iter))
+    }
+
+    /// Finds the Beta with E[ln x] = `ln_x` and E[ln(1 - x)] = `ln_1mx` by Newton's method from `init`.
+    fn solve_mean_ln(ln_x: T, ln_1mx: T, init: Self) -> (Self, bool, usize) {
+        let (mut a, mut b) = (init.a, init.b);
+        let tol = T::epsilon().sqrt();
+        let half = T::from(0.5).unwrap();
+        let mut converged = false;
+        let mut n_iter = 0;
+        while n_iter < 100 {
+            n_iter += 1;
+            let psi_ab = digamma(a + b);
+            let ga = psi_ab - digamma(a) + ln_x;
+            let gb = psi_ab - digamma(b) + ln_1mx;
+            let hab = trigamma(a + b);
+            let haa = hab - trigamma(a);
+            let hbb = hab - trigamma(b);
+            let det = haa * hbb - hab * hab;
+            let mut da = (hbb * ga - hab * gb) / det;
+            let mut db = (haa * gb - hab * ga) / det;
+            // Keep the parameters positive by damping the Newton step.
+            while a - da <= T::zero() || b - db <= T::zero() {
+                da *= half;
+                db *= half;
+            }
+            a -= da;
+            b -= db;
+            if da.abs() <= tol * a && db.abs() <= tol * b {
+                converged = true;
+                break;
+            }
+        }
+        (Self { a, b }, converged, n_iter)
+    }
 }
 
-impl<T: Float> Distribution<T> for Beta<T> {
+impl<T: FloatConst> Distribution<T> for Beta<T> {
     fn mean(&self) -> T {
         let a = self.a;
         let b = self.b;
@@ -50,19 +628,167 @@ impl<T: Float> Distribution<T> for Beta<T> {
         a * b / (a_plus_b * a_plus_b * (a_plus_b + T::one()))
     }
 
-    fn mode(&self) -> T {
+    fn mode(&self) -> Option<T> {
+        let one = T::one();
+        let a = self.a;
+        let b = self.b;
+        if a > one && b > one {
+            Some((a - one) / (a + b - T::from(2).unwrap()))
+        } else if a == one && b == one {
+            None
+        } else if a <= one && b >= one {
+            Some(T::zero())
+        } else if a >= one && b <= one {
+            Some(one)
+        } else {
+            // U-shaped, with modes at both 0 and 1.
+            None
+        }
+    }
+
+    fn entropy(&self) -> T {
+        let a = self.a;
+        let b = self.b;
+        let one = T::one();
+        ln_beta(a, b) - (a - one) * digamma(a) - (b - one) * digamma(b) + (a + b - one - one) * digamma(a + b)
+    }
+
+    fn skewness(&self) -> T {
+        let a = self.a;
+        let b = self.b;
+        let two = T::from(2).unwrap();
+        two * (b - a) * (a + b + T::one()).sqrt() / ((a + b + two) * (a * b).sqrt())
+    }
+
+    fn kurtosis(&self) -> T {
+        let a = self.a;
+        let b = self.b;
+        let ab = a * b;
+        let s = a + b;
+        let num = (a - b) * (a - b) * (s + T::one()) - ab * (s + T::from(2).unwrap());
+        T::from(6).unwrap() * num / (ab * (s + T::from(2).unwrap()) * (s + T::from(3).unwrap()))
+    }
+
+    fn ln_pdf(&self, x: T) -> T {
+        if x < T::zero() || x > T::one() {
+            return T::neg_infinity();
+        }
         let a = self.a;
         let b = self.b;
-        (a - T::one()) / (a + b - T::from(2).unwrap()) 
+        xlny(a - T::one(), x) + xlog1py(b - T::one(), -x) - ln_beta(a, b)
+    }
+
+    fn cdf(&self, x: T) -> T {
+        beta_inc(self.a, self.b, x)
+    }
+
+    fn quantile(&self, p: T) -> T {
+        beta_inc_inv(self.a, self.b, p)
+    }
+
+    fn sample(&self, rng: &mut Rng) -> T {
+        let x = rng.gamma(self.a);
+        let y = rng.gamma(self.b);
+        x / (x + y)
+    }
+}
+
+impl<T: FloatConst> ExponentialFamily<T> for Beta<T> {
+    fn natural_params(&self) -> Array1<T> {
+        arr1(&[self.a - T::one(), self.b - T::one()])
+    }
+
+    fn from_natural_params(eta: &Array1<T>) -> Result<Self, ParamError> {
+        check_len(eta, 2)?;
+        Self::new(eta[0] + T::one(), eta[1] + T::one())
+    }
+
+    fn sufficient_stats(x: T) -> Array1<T> {
+        arr1(&[x.ln(), (-x).ln_1p()])
+    }
+
+    fn ln_partition(eta: &Array1<T>) -> T {
+        ln_beta(eta[0] + T::one(), eta[1] + T::one())
+    }
+
+    fn ln_base_measure(x: T) -> T {
+        if x > T::zero() && x < T::one() {
+            T::zero()
+        } else {
+            T::neg_infinity()
+        }
+    }
+
+    fn mean_params(&self) -> Array1<T> {
+        let psi_ab = digamma(self.a + self.b);
+        arr1(&[digamma(self.a) - psi_ab, digamma(self.b) - psi_ab])
+    }
+
+    /// Solved by Newton's method, starting from the geometric-mean approximation.
+    fn from_mean_params(m: &Array1<T>) -> Result<Self, ParamError> {
+        check_len(m, 2)?;
+        let half = T::from(0.5).unwrap();
+        let gx = m[0].exp();
+        let g1mx = m[1].exp();
+        let denom = T::from(2).unwrap() * (T::one() - gx - g1mx);
+        let init = Self::new(half + gx / denom, half + g1mx / denom)?;
+        let (dist, _, _) = Self::solve_mean_ln(m[0], m[1], init);
+        Self::new(dist.a, dist.b)
     }
 }
 
 pub struct Gaussian<T: Float> {
-    pub mu: T,
-    pub sigma2: T,
+    mu: T,
+    sigma2: T,
+}
+
+impl<T: FloatConst> Gaussian<T> {
+    pub fn new(mu: T, sigma2: T) -> Result<Self, ParamError> {
+        check_finite("mu", mu)?;
+        check_positive("sigma2", sigma2)?;
+        Ok(Self { mu, sigma2 })
+    }
+
+    pub fn mu(&self) -> T {
+        self.mu
+    }
+
+    pub fn sigma2(&self) -> T {
+        self.sigma2
+    }
+
+    /// Maximum likelihood estimate (the variance is the biased sample variance).
+    pub fn fit(x: &Array1<T>) -> Result<FitResult<T, Self>, ParamError> {
+        check_nonempty(x)?;
+        let mu = x.mean().unwrap();
+        let sigma2 = x.var_axis(Axis(0), T::zero()).into_scalar();
+        Ok(FitResult::new(Self::new(mu, sigma2)?, x, true, 0))
+    }
+
+    /// Posterior over the mean of a Gaussian with known variance `sigma2`, with `self` as the prior.
+    pub fn posterior(&self, sigma2: T, x: &Array1<T>) -> Result<Self, ParamError> {
+        check_positive("sigma2", sigma2)?;
+        let n = T::from(x.len()).unwrap();
+        let prec = T::one() / self.sigma2 + n / sigma2;
+        let mu = (self.mu / self.sigma2 + x.sum() / sigma2) / prec;
+        Self::new(mu, T::one() / prec)
+    }
+
+    /// Predictive distribution of the next observation, given the known variance `sigma2`.
+    pub fn posterior_predictive(&self, sigma2: T) -> Result<Self, ParamError> {
+        check_positive("sigma2", sigma2)?;
+        Self::new(self.mu, self.sigma2 + sigma2)
+    }
+
+    /// KL(self || other)
+    pub fn kl_divergence(&self, other: &Self) -> T {
+        let d = self.mu - other.mu;
+        let ratio = self.sigma2 / other.sigma2;
+        T::from(0.5).unwrap() * (ratio - ratio.ln() + d * d / other.sigma2 - T::one())
+    }
 }
 
-impl<T: Float> Distribution<T> for Gaussian<T> {
+impl<T: FloatConst> Distribution<T> for Gaussian<T> {
     fn mean(&self) -> T {
         self.mu
     }
@@ -71,7 +797,3919 @@ impl<T: Float> Distribution<T> for Gaussian<T> {
         self.sigma2
     }
 
-    fn mode(&self) -> T {
-        self.mu
+    fn mode(&self) -> Option<T> {
+        Some(self.mu)
+    }
+
+    fn entropy(&self) -> T {
+        let half = T::from(0.5).unwrap();
+        T::LN_TWO_PI_SQRT + half + half * self.sigma2.ln()
+    }
+
+    fn skewness(&self) -> T {
+        T::zero()
+    }
+
+    fn kurtosis(&self) -> T {
+        T::zero()
+    }
+
+    fn pdf(&self, x: T) -> T {
+        let d = x - self.mu;
+        T::TWO_PI_SQRT_INV / self.sigma2.sqrt() * (-d * d / (self.sigma2 + self.sigma2)).exp()
+    }
+
+    fn ln_pdf(&self, x: T) -> T {
+        let d = x - self.mu;
+        -T::LN_TWO_PI_SQRT - T::from(0.5).unwrap() * self.sigma2.ln() - d * d / (self.sigma2 + self.sigma2)
+    }
+
+    fn cdf(&self, x: T) -> T {
+        let z = (x - self.mu) / (self.sigma2 + self.sigma2).sqrt();
+        T::from(0.5).unwrap() * erfc(-z)
+    }
+
+    fn sf(&self, x: T) -> T {
+        let z = (x - self.mu) / (self.sigma2 + self.sigma2).sqrt();
+        T::from(0.5).unwrap() * erfc(z)
+    }
+
+    fn quantile(&self, p: T) -> T {
+        self.mu - (self.sigma2 + self.sigma2).sqrt() * erfcinv(p + p)
+    }
+
+    fn isf(&self, p: T) -> T {
+        self.mu + (self.sigma2 + self.sigma2).sqrt() * erfcinv(p + p)
     }
-}
\ No newline at end of file
+
+    fn sample(&self, rng: &mut Rng) -> T {
+        self.mu + self.sigma2.sqrt() * rng.normal()
+    }
+}
+
+impl<T: FloatConst> ExponentialFamily<T> for Gaussian<T> {
+    fn natural_params(&self) -> Array1<T> {
+        arr1(&[self.mu / self.sigma2, -T::one() / (self.sigma2 + self.sigma2)])
+    }
+
+    fn from_natural_params(eta: &Array1<T>) -> Result<Self, ParamError> {
+        check_len(eta, 2)?;
+        let sigma2 = -T::one() / (eta[1] + eta[1]);
+        Self::new(eta[0] * sigma2, sigma2)
+    }
+
+    fn sufficient_stats(x: T) -> Array1<T> {
+        arr1(&[x, x * x])
+    }
+
+    fn ln_partition(eta: &Array1<T>) -> T {
+        let half = T::from(0.5).unwrap();
+        -eta[0] * eta[0] / (T::from(4).unwrap() * eta[1]) - half * (-(eta[1] + eta[1])).ln()
+    }
+
+    fn ln_base_measure(_x: T) -> T {
+        -T::LN_TWO_PI_SQRT
+    }
+
+    fn mean_params(&self) -> Array1<T> {
+        arr1(&[self.mu, self.mu * self.mu + self.sigma2])
+    }
+
+    fn from_mean_params(m: &Array1<T>) -> Result<Self, ParamError> {
+        check_len(m, 2)?;
+        Self::new(m[0], m[1] - m[0] * m[0])
+    }
+}
+
+/// Student's t distribution with location `mu` and squared scale `sigma2`.
+pub struct StudentT<T: Float> {
+    nu: T,
+    mu: T,
+    sigma2: T,
+}
+
+impl<T: FloatConst> StudentT<T> {
+    pub fn new(nu: T, mu: T, sigma2: T) -> Result<Self, ParamError> {
+        check_positive("nu", nu)?;
+        check_finite("mu", mu)?;
+        check_positive("sigma2", sigma2)?;
+        Ok(Self { nu, mu, sigma2 })
+    }
+
+    /// Probability beyond `x` in the tail it lies in, P(|X - mu| >= |x - mu|) / 2.
+    fn tail(&self, x: T) -> T {
+        let half = T::from(0.5).unwrap();
+        let t = (x - self.mu) / self.sigma2.sqrt();
+        half * beta_inc(half * self.nu, half, self.nu / (self.nu + t * t))
+    }
+
+    /// quantile(p) - mu, computed from whichever tail `p` is closer to.
+    fn offset(&self, p: T) -> T {
+        let half = T::from(0.5).unwrap();
+        let pp = if p < half { p } else { T::one() - p };
+        let xb = beta_inc_inv(half * self.nu, half, pp + pp);
+        let t = (self.nu * (T::one() - xb) / xb).sqrt() * self.sigma2.sqrt();
+        if p < half {
+            -t
+        } else {
+            t
+        }
+    }
+
+    pub fn nu(&self) -> T {
+        self.nu
+    }
+
+    pub fn mu(&self) -> T {
+        self.mu
+    }
+
+    pub fn sigma2(&self) -> T {
+        self.sigma2
+    }
+}
+
+impl<T: FloatConst> Distribution<T> for StudentT<T> {
+    fn mean(&self) -> T {
+        if self.nu > T::one() {
+            self.mu
+        } else {
+            T::nan()
+        }
+    }
+
+    fn var(&self) -> T {
+        let two = T::from(2).unwrap();
+        if self.nu > two {
+            self.sigma2 * self.nu / (self.nu - two)
+        } else if self.nu > T::one() {
+            T::infinity()
+        } else {
+            T::nan()
+        }
+    }
+
+    fn mode(&self) -> Option<T> {
+        Some(self.mu)
+    }
+
+    fn entropy(&self) -> T {
+        let half = T::from(0.5).unwrap();
+        let nu = self.nu;
+        let h = half * (nu + T::one());
+        h * (digamma(h) - digamma(half * nu)) + half * nu.ln() + ln_beta(half * nu, half) + half * self.sigma2.ln()
+    }
+
+    fn skewness(&self) -> T {
+        if self.nu > T::from(3).unwrap() {
+            T::zero()
+        } else {
+            T::nan()
+        }
+    }
+
+    fn kurtosis(&self) -> T {
+        let four = T::from(4).unwrap();
+        if self.nu > four {
+            T::from(6).unwrap() / (self.nu - four)
+        } else if self.nu > T::from(2).unwrap() {
+            T::infinity()
+        } else {
+            T::nan()
+        }
+    }
+
+    fn ln_pdf(&self, x: T) -> T {
+        let half = T::from(0.5).unwrap();
+        let nu = self.nu;
+        let d = x - self.mu;
+        -ln_beta(half * nu, half) - half * (nu * self.sigma2).ln()
+            - half * (nu + T::one()) * (d * d / (nu * self.sigma2)).ln_1p()
+    }
+
+    fn cdf(&self, x: T) -> T {
+        let p = self.tail(x);
+        if x > self.mu {
+            T::one() - p
+        } else {
+            p
+        }
+    }
+
+    fn sf(&self, x: T) -> T {
+        let p = self.tail(x);
+        if x > self.mu {
+            p
+        } else {
+            T::one() - p
+        }
+    }
+
+    fn quantile(&self, p: T) -> T {
+        self.mu + self.offset(p)
+    }
+
+    fn isf(&self, p: T) -> T {
+        self.mu - self.offset(p)
+    }
+
+    fn sample(&self, rng: &mut Rng) -> T {
+        let half = T::from(0.5).unwrap();
+        let z: T = rng.normal();
+        let chi2 = rng.gamma(half * self.nu) * T::from(2).unwrap();
+        self.mu + self.sigma2.sqrt() * z / (chi2 / self.nu).sqrt()
+    }
+}
+
+/// Normal-Gamma prior over the mean and precision of a Gaussian.
+///
+/// The mean is Gaussian with precision `lambda * tau` around `mu`, and the precision `tau` is Gamma(`a`, `b`).
+pub struct NormalGamma<T: Float> {
+    mu: T,
+    lambda: T,
+    a: T,
+    b: T,
+}
+
+impl<T: FloatConst> NormalGamma<T> {
+    pub fn new(mu: T, lambda: T, a: T, b: T) -> Result<Self, ParamError> {
+        check_finite("mu", mu)?;
+        check_positive("lambda", lambda)?;
+        check_positive("a", a)?;
+        check_positive("b", b)?;
+        Ok(Self { mu, lambda, a, b })
+    }
+
+    pub fn mu(&self) -> T {
+        self.mu
+    }
+
+    pub fn lambda(&self) -> T {
+        self.lambda
+    }
+
+    pub fn a(&self) -> T {
+        self.a
+    }
+
+    pub fn b(&self) -> T {
+        self.b
+    }
+
+    /// Posterior after observing `x` from a Gaussian with unknown mean and precision.
+    pub fn posterior(&self, x: &Array1<T>) -> Self {
+        let half = T::from(0.5).unwrap();
+        let n = T::from(x.len()).unwrap();
+        let x_mean = x.mean().unwrap_or(self.mu);
+        let ss = x.iter().map(|&xi| (xi - x_mean) * (xi - x_mean)).sum::<T>();
+        let d = x_mean - self.mu;
+        let lambda = self.lambda + n;
+        let mu = (self.lambda * self.mu + n * x_mean) / lambda;
+        let a = self.a + half * n;
+        let b = self.b + half * ss + half * self.lambda * n * d * d / lambda;
+        Self { mu, lambda, a, b }
+    }
+
+    /// Predictive distribution of the next observation.
+    pub fn posterior_predictive(&self) -> StudentT<T> {
+        let nu = self.a + self.a;
+        let sigma2 = self.b * (self.lambda + T::one()) / (self.a * self.lambda);
+        StudentT { nu, mu: self.mu, sigma2 }
+    }
+}
+
+/// Gamma distribution with shape `a` and rate `b`.
+pub struct Gamma<T: Float> {
+    a: T,
+    b: T,
+}
+
+impl<T: FloatConst> Gamma<T> {
+    pub fn new(a: T, b: T) -> Result<Self, ParamError> {
+        check_positive("a", a)?;
+        check_positive("b", b)?;
+        Ok(Self { a, b })
+    }
+
+    pub fn a(&self) -> T {
+        self.a
+    }
+
+    pub fn b(&self) -> T {
+        self.b
+    }
+}
+
+impl<T: FloatConst> Distribution<T> for Gamma<T> {
+    fn mean(&self) -> T {
+        self.a / self.b
+    }
+
+    fn var(&self) -> T {
+        self.a / (self.b * self.b)
+    }
+
+    fn mode(&self) -> Option<T> {
+        if self.a >= T::one() {
+            Some((self.a - T::one()) / self.b)
+        } else {
+            Some(T::zero())
+        }
+    }
+
+    fn entropy(&self) -> T {
+        let a = self.a;
+        a - self.b.ln() + ln_gamma(a) + (T::one() - a) * digamma(a)
+    }
+
+    fn skewness(&self) -> T {
+        T::from(2).unwrap() / self.a.sqrt()
+    }
+
+    fn kurtosis(&self) -> T {
+        T::from(6).unwrap() / self.a
+    }
+
+    fn ln_pdf(&self, x: T) -> T {
+        if x < T::zero() {
+            return T::neg_infinity();
+        }
+        let a = self.a;
+        let b = self.b;
+        a * b.ln() - ln_gamma(a) + xlny(a - T::one(), x) - b * x
+    }
+
+    fn cdf(&self, x: T) -> T {
+        gamma_p(self.a, self.b * x)
+    }
+
+    fn sf(&self, x: T) -> T {
+        gamma_q(self.a, self.b * x)
+    }
+
+    fn quantile(&self, p: T) -> T {
+        gamma_p_inv(self.a, p) / self.b
+    }
+
+    fn isf(&self, p: T) -> T {
+        gamma_q_inv(self.a, p) / self.b
+    }
+
+    fn sample(&self, rng: &mut Rng) -> T {
+        rng.gamma(self.a) / self.b
+    }
+}
+
+/// Exponential distribution with rate `lambda`.
+pub struct Exponential<T: Float> {
+    lambda: T,
+}
+
+impl<T: Float> Exponential<T> {
+    pub fn new(lambda: T) -> Result<Self, ParamError> {
+        check_positive("lambda", lambda)?;
+        Ok(Self { lambda })
+    }
+
+    pub fn lambda(&self) -> T {
+        self.lambda
+    }
+}
+
+impl<T: Float> Distribution<T> for Exponential<T> {
+    fn mean(&self) -> T {
+        T::one() / self.lambda
+    }
+
+    fn var(&self) -> T {
+        T::one() / (self.lambda * self.lambda)
+    }
+
+    fn mode(&self) -> Option<T> {
+        Some(T::zero())
+    }
+
+    fn entropy(&self) -> T {
+        T::one() - self.lambda.ln()
+    }
+
+    fn skewness(&self) -> T {
+        T::from(2).unwrap()
+    }
+
+    fn kurtosis(&self) -> T {
+        T::from(6).unwrap()
+    }
+
+    fn ln_pdf(&self, x: T) -> T {
+        if x < T::zero() {
+            T::neg_infinity()
+        } else {
+            self.lambda.ln() - self.lambda * x
+        }
+    }
+
+    fn cdf(&self, x: T) -> T {
+        if x < T::zero() {
+            T::zero()
+        } else {
+            -(-self.lambda * x).exp_m1()
+        }
+    }
+
+    fn sf(&self, x: T) -> T {
+        if x < T::zero() {
+            T::one()
+        } else {
+            (-self.lambda * x).exp()
+        }
+    }
+
+    fn quantile(&self, p: T) -> T {
+        -(-p).ln_1p() / self.lambda
+    }
+
+    fn isf(&self, p: T) -> T {
+        -p.ln() / self.lambda
+    }
+
+    fn sample(&self, rng: &mut Rng) -> T {
+        self.quantile(rng.uniform())
+    }
+}
+
+/// Chi-squared distribution with `k` degrees of freedom.
+pub struct ChiSquared<T: Float> {
+    k: T,
+}
+
+impl<T: FloatConst> ChiSquared<T> {
+    pub fn new(k: T) -> Result<Self, ParamError> {
+        check_positive("k", k)?;
+        Ok(Self { k })
+    }
+
+    pub fn k(&self) -> T {
+        self.k
+    }
+
+    fn as_gamma(&self) -> Gamma<T> {
+        let half = T::from(0.5).unwrap();
+        Gamma { a: half * self.k, b: half }
+    }
+}
+
+impl<T: FloatConst> Distribution<T> for ChiSquared<T> {
+    fn mean(&self) -> T {
+        self.k
+    }
+
+    fn var(&self) -> T {
+        self.k + self.k
+    }
+
+    fn mode(&self) -> Option<T> {
+        let two = T::from(2).unwrap();
+        if self.k > two {
+            Some(self.k - two)
+        } else {
+            Some(T::zero())
+        }
+    }
+
+    fn entropy(&self) -> T {
+        self.as_gamma().entropy()
+    }
+
+    fn skewness(&self) -> T {
+        self.as_gamma().skewness()
+    }
+
+    fn kurtosis(&self) -> T {
+        self.as_gamma().kurtosis()
+    }
+
+    fn ln_pdf(&self, x: T) -> T {
+        self.as_gamma().ln_pdf(x)
+    }
+
+    fn cdf(&self, x: T) -> T {
+        self.as_gamma().cdf(x)
+    }
+
+    fn sf(&self, x: T) -> T {
+        self.as_gamma().sf(x)
+    }
+
+    fn quantile(&self, p: T) -> T {
+        self.as_gamma().quantile(p)
+    }
+
+    fn isf(&self, p: T) -> T {
+        self.as_gamma().isf(p)
+    }
+
+    fn sample(&self, rng: &mut Rng) -> T {
+        self.as_gamma().sample(rng)
+    }
+}
+
+/// Laplace distribution with location `mu` and scale `b`.
+pub struct Laplace<T: Float> {
+    mu: T,
+    b: T,
+}
+
+impl<T: Float> Laplace<T> {
+    pub fn new(mu: T, b: T) -> Result<Self, ParamError> {
+        check_finite("mu", mu)?;
+        check_positive("b", b)?;
+        Ok(Self { mu, b })
+    }
+
+    pub fn mu(&self) -> T {
+        self.mu
+    }
+
+    pub fn b(&self) -> T {
+        self.b
+    }
+}
+
+impl<T: Float> Distribution<T> for Laplace<T> {
+    fn mean(&self) -> T {
+        self.mu
+    }
+
+    fn var(&self) -> T {
+        T::from(2).unwrap() * self.b * self.b
+    }
+
+    fn mode(&self) -> Option<T> {
+        Some(self.mu)
+    }
+
+    fn entropy(&self) -> T {
+        T::one() + (self.b + self.b).ln()
+    }
+
+    fn skewness(&self) -> T {
+        T::zero()
+    }
+
+    fn kurtosis(&self) -> T {
+        T::from(3).unwrap()
+    }
+
+    fn ln_pdf(&self, x: T) -> T {
+        -(self.b + self.b).ln() - (x - self.mu).abs() / self.b
+    }
+
+    fn cdf(&self, x: T) -> T {
+        let half = T::from(0.5).unwrap();
+        let z = (x - self.mu) / self.b;
+        if z < T::zero() {
+            half * z.exp()
+        } else {
+            T::one() - half * (-z).exp()
+        }
+    }
+
+    fn sf(&self, x: T) -> T {
+        let half = T::from(0.5).unwrap();
+        let z = (x - self.mu) / self.b;
+        if z > T::zero() {
+            half * (-z).exp()
+        } else {
+            T::one() - half * z.exp()
+        }
+    }
+
+    fn quantile(&self, p: T) -> T {
+        let half = T::from(0.5).unwrap();
+        if p < half {
+            self.mu + self.b * (p + p).ln()
+        } else {
+            self.mu - self.b * (T::from(2).unwrap() * (T::one() - p)).ln()
+        }
+    }
+
+    fn isf(&self, p: T) -> T {
+        let half = T::from(0.5).unwrap();
+        if p < half {
+            self.mu - self.b * (p + p).ln()
+        } else {
+            self.mu + self.b * (T::from(2).unwrap() * (T::one() - p)).ln()
+        }
+    }
+
+    fn sample(&self, rng: &mut Rng) -> T {
+        self.quantile(rng.uniform())
+    }
+}
+
+/// Continuous uniform distribution on [`a`, `b`].
+pub struct Uniform<T: Float> {
+    a: T,
+    b: T,
+}
+
+impl<T: Float> Uniform<T> {
+    pub fn new(a: T, b: T) -> Result<Self, ParamError> {
+        check_finite("a", a)?;
+        check_finite("b", b)?;
+        if a < b {
+            Ok(Self { a, b })
+        } else {
+            Err(ParamError::EmptyRange)
+        }
+    }
+
+    pub fn a(&self) -> T {
+        self.a
+    }
+
+    pub fn b(&self) -> T {
+        self.b
+    }
+}
+
+impl<T: Float> Distribution<T> for Uniform<T> {
+    fn mean(&self) -> T {
+        (self.a + self.b) / T::from(2).unwrap()
+    }
+
+    fn var(&self) -> T {
+        let d = self.b - self.a;
+        d * d / T::from(12).unwrap()
+    }
+
+    /// Every point of [a, b] is a mode, so this is `None`.
+    fn mode(&self) -> Option<T> {
+        None
+    }
+
+    fn entropy(&self) -> T {
+        (self.b - self.a).ln()
+    }
+
+    fn skewness(&self) -> T {
+        T::zero()
+    }
+
+    fn kurtosis(&self) -> T {
+        T::from(-1.2).unwrap()
+    }
+
+    fn ln_pdf(&self, x: T) -> T {
+        if x < self.a || x > self.b {
+            T::neg_infinity()
+        } else {
+            -(self.b - self.a).ln()
+        }
+    }
+
+    fn cdf(&self, x: T) -> T {
+        if x <= self.a {
+            T::zero()
+        } else if x >= self.b {
+            T::one()
+        } else {
+            (x - self.a) / (self.b - self.a)
+        }
+    }
+
+    fn quantile(&self, p: T) -> T {
+        self.a + p * (self.b - self.a)
+    }
+
+    fn sample(&self, rng: &mut Rng) -> T {
+        self.quantile(rng.uniform())
+    }
+}
+
+/// Cauchy distribution with location `mu` and scale `gamma`. Its mean and variance are undefined (NaN).
+pub struct Cauchy<T: Float> {
+    mu: T,
+    gamma: T,
+}
+
+impl<T: Float> Cauchy<T> {
+    pub fn new(mu: T, gamma: T) -> Result<Self, ParamError> {
+        check_finite("mu", mu)?;
+        check_positive("gamma", gamma)?;
+        Ok(Self { mu, gamma })
+    }
+
+    pub fn mu(&self) -> T {
+        self.mu
+    }
+
+    pub fn gamma(&self) -> T {
+        self.gamma
+    }
+}
+
+impl<T: Float> Distribution<T> for Cauchy<T> {
+    fn mean(&self) -> T {
+        T::nan()
+    }
+
+    fn var(&self) -> T {
+        T::nan()
+    }
+
+    fn mode(&self) -> Option<T> {
+        Some(self.mu)
+    }
+
+    fn entropy(&self) -> T {
+        let pi = T::from(std::f64::consts::PI).unwrap();
+        (T::from(4).unwrap() * pi * self.gamma).ln()
+    }
+
+    fn skewness(&self) -> T {
+        T::nan()
+    }
+
+    fn kurtosis(&self) -> T {
+        T::nan()
+    }
+
+    fn ln_pdf(&self, x: T) -> T {
+        let pi = T::from(std::f64::consts::PI).unwrap();
+        let z = (x - self.mu) / self.gamma;
+        -(pi * self.gamma).ln() - (z * z).ln_1p()
+    }
+
+    fn cdf(&self, x: T) -> T {
+        let pi = T::from(std::f64::consts::PI).unwrap();
+        let z = (x - self.mu) / self.gamma;
+        T::from(0.5).unwrap() + z.atan() / pi
+    }
+
+    fn sf(&self, x: T) -> T {
+        let pi = T::from(std::f64::consts::PI).unwrap();
+        let z = (x - self.mu) / self.gamma;
+        if z > T::zero() {
+            z.recip().atan() / pi
+        } else {
+            T::from(0.5).unwrap() - z.atan() / pi
+        }
+    }
+
+    fn quantile(&self, p: T) -> T {
+        let pi = T::from(std::f64::consts::PI).unwrap();
+        self.mu + self.gamma * (pi * (p - T::from(0.5).unwrap())).tan()
+    }
+
+    fn isf(&self, p: T) -> T {
+        let pi = T::from(std::f64::consts::PI).unwrap();
+        self.mu + self.gamma / (pi * p).tan()
+    }
+
+    fn sample(&self, rng: &mut Rng) -> T {
+        self.quantile(rng.uniform())
+    }
+}
+
+/// Von Mises distribution of an angle with mean direction `mu` and concentration `kappa`.
+pub struct VonMises<T: Float> {
+    mu: T,
+    kappa: T,
+}
+
+impl<T: FloatConst> VonMises<T> {
+    pub fn new(mu: T, kappa: T) -> Result<Self, ParamError> {
+        check_finite("mu", mu)?;
+        check_positive("kappa", kappa)?;
+        Ok(Self { mu, kappa })
+    }
+
+    pub fn mu(&self) -> T {
+        self.mu
+    }
+
+    pub fn kappa(&self) -> T {
+        self.kappa
+    }
+
+    /// A(kappa) = I1(kappa) / I0(kappa), the mean resultant length.
+    fn a(kappa: T) -> T {
+        bessel_i1e(kappa) / bessel_i0e(kappa)
+    }
+
+    /// Maximum likelihood estimate. `kappa` solves A(kappa) = R by Newton's method from the Best-Fisher
+    /// approximation, where R is the sample mean resultant length.
+    pub fn fit(x: &Array1<T>) -> Result<FitResult<T, Self>, ParamError> {
+        check_nonempty(x)?;
+        let c = |v: f64| T::from(v).unwrap();
+        let (mu, r) = mean_direction(x);
+        let mut kappa = if r < c(0.53) {
+            r * (c(2.0) + r * r * (T::one() + c(5.0 / 6.0) * r * r))
+        } else if r < c(0.85) {
+            c(-0.4) + c(1.39) * r + c(0.43) / (T::one() - r)
+        } else {
+            T::one() / (r * (c(3.0) + r * (r - c(4.0))))
+        };
+        let tol = T::epsilon().sqrt();
+        let mut converged = false;
+        let mut n_iter = 0;
+        while n_iter < 100 && kappa > T::zero() && kappa.is_finite() {
+            n_iter += 1;
+            let a = Self::a(kappa);
+            let da = T::one() - a / kappa - a * a;
+            let mut dk = (a - r) / da;
+            while kappa - dk <= T::zero() {
+                dk *= c(0.5);
+            }
+            kappa -= dk;
+            if dk.abs() <= tol * kappa {
+                converged = true;
+                break;
+            }
+        }
+        Ok(FitResult::new(Self::new(mu, kappa)?, x, converged, n_iter))
+    }
+}
+
+impl<T: FloatConst> Distribution<T> for VonMises<T> {
+    fn mean(&self) -> T {
+        wrap_angle(self.mu)
+    }
+
+    fn var(&self) -> T {
+        T::one() - Self::a(self.kappa)
+    }
+
+    fn std(&self) -> T {
+        (T::from(-2).unwrap() * Self::a(self.kappa).ln()).sqrt()
+    }
+
+    fn mode(&self) -> Option<T> {
+        Some(wrap_angle(self.mu))
+    }
+
+    fn entropy(&self) -> T {
+        let two_pi = T::from(2.0 * std::f64::consts::PI).unwrap();
+        (two_pi * bessel_i0e(self.kappa)).ln() + self.kappa * (T::one() - Self::a(self.kappa))
+    }
+
+    fn skewness(&self) -> T {
+        T::zero()
+    }
+
+    fn kurtosis(&self) -> T {
+        let r = Self::a(self.kappa);
+        let r2 = r * r;
+        (self.trig_moment(2) - r2 * r2) / ((T::one() - r) * (T::one() - r))
+    }
+
+    fn ln_pdf(&self, x: T) -> T {
+        let two_pi = T::from(2.0 * std::f64::consts::PI).unwrap();
+        // cos(x - mu) - 1 written without the cancellation near the mode
+        let s = ((x - self.mu) * T::from(0.5).unwrap()).sin();
+        T::from(-2).unwrap() * self.kappa * s * s - (two_pi * bessel_i0e(self.kappa)).ln()
+    }
+
+    /// Fourier series with coefficients I_n(kappa) / I_0(kappa), which fall off like exp(-n^2 / (2 kappa)).
+    fn cdf(&self, x: T) -> T {
+        let pi = T::from(std::f64::consts::PI).unwrap();
+        if x < -pi {
+            return T::zero();
+        }
+        if x >= pi {
+            return T::one();
+        }
+        let n = 28 + (9.0 * self.kappa.to_f64().unwrap().sqrt()).ceil() as usize;
+        // I_k / I_(k-1) by backward recurrence, started from its large-order limit kappa / (k + sqrt(k^2 + kappa^2)).
+        let two = T::from(2).unwrap();
+        let kappa = self.kappa;
+        let k = T::from(n + 11).unwrap();
+        let mut ratio = kappa / (k + (k * k + kappa * kappa).sqrt());
+        let mut rho = vec![T::zero(); n];
+        for k in (1..=n + 10).rev() {
+            ratio = T::one() / (two * T::from(k).unwrap() / kappa + ratio);
+            if k <= n {
+                rho[k - 1] = ratio;
+            }
+        }
+        let mut prod = T::one();
+        for r in rho.iter_mut() {
+            prod *= *r;
+            *r = prod;
+        }
+        circular_cdf_series(x, wrap_angle(self.mu), &rho)
+    }
+
+    fn quantile(&self, p: T) -> T {
+        circular_quantile(self, p)
+    }
+
+    /// Best and Fisher's rejection sampler, with a wrapped Gaussian for very large `kappa`.
+    fn sample(&self, rng: &mut Rng) -> T {
+        let one = T::one();
+        let c = |v: f64| T::from(v).unwrap();
+        let pi = c(std::f64::consts::PI);
+        let kappa = self.kappa;
+        if kappa < c(1e-8) {
+            return pi * (rng.uniform::<T>() * c(2.0) - one);
+        }
+        if kappa > c(1e6) {
+            return wrap_angle(self.mu + rng.normal::<T>() / kappa.sqrt());
+        }
+        let s = if kappa < c(1e-5) {
+            one / kappa + kappa
+        } else {
+            let r = one + (one + c(4.0) * kappa * kappa).sqrt();
+            let rho = (r - (r + r).sqrt()) / (kappa + kappa);
+            (one + rho * rho) / (rho + rho)
+        };
+        loop {
+            let z = (pi * rng.uniform::<T>()).cos();
+            let w = (one + s * z) / (s + z);
+            let y = kappa * (s - w);
+            let v = rng.uniform::<T>();
+            if y * (c(2.0) - y) - v >= T::zero() || (y / v).ln() + one - y >= T::zero() {
+                let theta = w.max(-one).min(one).acos();
+                let theta = if rng.uniform::<T>() < c(0.5) { -theta } else { theta };
+                return wrap_angle(self.mu + theta);
+            }
+        }
+    }
+}
+
+impl<T: FloatConst> Circular<T> for VonMises<T> {
+    fn trig_moment(&self, p: usize) -> T {
+        match p {
+            0 => T::one(),
+            1 => Self::a(self.kappa),
+            _ => bessel_ine(p, self.kappa) / bessel_i0e(self.kappa),
+        }
+    }
+}
+
+/// Gaussian with mean `mu` and variance `sigma2` wrapped onto the circle.
+pub struct WrappedGaussian<T: Float> {
+    mu: T,
+    sigma2: T,
+}
+
+impl<T: FloatConst> WrappedGaussian<T> {
+    pub fn new(mu: T, sigma2: T) -> Result<Self, ParamError> {
+        check_finite("mu", mu)?;
+        check_positive("sigma2", sigma2)?;
+        Ok(Self { mu, sigma2 })
+    }
+
+    pub fn mu(&self) -> T {
+        self.mu
+    }
+
+    pub fn sigma2(&self) -> T {
+        self.sigma2
+    }
+
+    /// Method of moments estimate, sigma2 = -2 ln R with R the sample mean resultant length.
+    pub fn fit_moments(x: &Array1<T>) -> Result<Self, ParamError> {
+        check_nonempty(x)?;
+        let (mu, r) = mean_direction(x);
+        Self::new(mu, T::from(-2).unwrap() * r.ln())
+    }
+
+    /// Below this variance the density is summed over wraps of the Gaussian, above it as a Fourier series.
+    fn wraps(&self) -> bool {
+        self.sigma2 < T::from(2.0 * std::f64::consts::PI).unwrap()
+    }
+
+    /// Fourier coefficients exp(-n^2 sigma2 / 2) down to rounding level.
+    fn rho(&self) -> Vec<T> {
+        let half = T::from(0.5).unwrap();
+        (1..)
+            .map(|n| (-half * T::from(n * n).unwrap() * self.sigma2).exp())
+            .take_while(|&r| r > T::epsilon() * T::epsilon())
+            .collect()
+    }
+}
+
+impl<T: FloatConst> Distribution<T> for WrappedGaussian<T> {
+    fn mean(&self) -> T {
+        wrap_angle(self.mu)
+    }
+
+    fn var(&self) -> T {
+        -(T::from(-0.5).unwrap() * self.sigma2).exp_m1()
+    }
+
+    fn std(&self) -> T {
+        self.sigma2.sqrt()
+    }
+
+    fn mode(&self) -> Option<T> {
+        Some(wrap_angle(self.mu))
+    }
+
+    /// Exact for the Gaussian when the wraps are negligible, periodic trapezoid rule otherwise.
+    fn entropy(&self) -> T {
+        let half = T::from(0.5).unwrap();
+        if self.sigma2 < T::from(0.1).unwrap() {
+            return T::LN_TWO_PI_SQRT + half + half * self.sigma2.ln();
+        }
+        let pi = T::from(std::f64::consts::PI).unwrap();
+        let n = 64;
+        let h = (pi + pi) / T::from(n).unwrap();
+        let mut sum = T::zero();
+        for i in 0..n {
+            let lp = self.ln_pdf(-pi + h * T::from(i).unwrap());
+            sum -= lp.exp() * lp;
+        }
+        sum * h
+    }
+
+    fn skewness(&self) -> T {
+        T::zero()
+    }
+
+    fn kurtosis(&self) -> T {
+        let r = self.mean_resultant_length();
+        let r2 = r * r;
+        (self.trig_moment(2) - r2 * r2) / (self.var() * self.var())
+    }
+
+    fn ln_pdf(&self, x: T) -> T {
+        let pi = T::from(std::f64::consts::PI).unwrap();
+        let d = wrap_angle(x - self.mu);
+        if self.wraps() {
+            // Sum relative to the k = 0 term: (d + 2 pi k)^2 - d^2 = 2 pi k (2 d + 2 pi k).
+            let mut s = T::zero();
+            for k in -5..=5 {
+                let tk = (pi + pi) * T::from(k).unwrap();
+                s += (-tk * (d + d + tk) / (self.sigma2 + self.sigma2)).exp();
+            }
+            -T::LN_TWO_PI_SQRT - T::from(0.5).unwrap() * self.sigma2.ln() - d * d / (self.sigma2 + self.sigma2) + s.ln()
+        } else {
+            let mut s = T::one();
+            for (i, &r) in self.rho().iter().enumerate() {
+                s += (r + r) * (T::from(i + 1).unwrap() * d).cos();
+            }
+            (s / (pi + pi)).ln()
+        }
+    }
+
+    fn cdf(&self, x: T) -> T {
+        let pi = T::from(std::f64::consts::PI).unwrap();
+        if x < -pi {
+            return T::zero();
+        }
+        if x >= pi {
+            return T::one();
+        }
+        let mu = wrap_angle(self.mu);
+        if !self.wraps() {
+            return circular_cdf_series(x, mu, &self.rho());
+        }
+        // Mass of each shifted Gaussian on [-pi, x], as erfc differences that avoid cancelling near one.
+        let half = T::from(0.5).unwrap();
+        let scale = (self.sigma2 + self.sigma2).sqrt();
+        let mut sum = T::zero();
+        for k in -5..=5 {
+            let tk = (pi + pi) * T::from(k).unwrap();
+            let a = (-pi - mu + tk) / scale;
+            let b = (x - mu + tk) / scale;
+            sum += if a >= T::zero() {
+                half * (erfc(a) - erfc(b))
+            } else if b <= T::zero() {
+                half * (erfc(-b) - erfc(-a))
+            } else {
+                T::one() - half * (erfc(b) + erfc(-a))
+            };
+        }
+        sum.max(T::zero()).min(T::one())
+    }
+
+    fn quantile(&self, p: T) -> T {
+        circular_quantile(self, p)
+    }
+
+    fn sample(&self, rng: &mut Rng) -> T {
+        wrap_angle(self.mu + self.sigma2.sqrt() * rng.normal())
+    }
+}
+
+impl<T: FloatConst> Circular<T> for WrappedGaussian<T> {
+    fn trig_moment(&self, p: usize) -> T {
+        let p = T::from(p).unwrap();
+        (T::from(-0.5).unwrap() * p * p * self.sigma2).exp()
+    }
+}
+
+/// Number of successes in `n` independent Bernoulli trials with success probability `p`.
+pub struct Binomial<T: Float> {
+    n: usize,
+    p: T,
+}
+
+impl<T: FloatConst> Binomial<T> {
+    pub fn new(n: usize, p: T) -> Result<Self, ParamError> {
+        check_probability("p", p)?;
+        Ok(Self { n, p })
+    }
+
+    pub fn n(&self) -> usize {
+        self.n
+    }
+
+    pub fn p(&self) -> T {
+        self.p
+    }
+
+    /// Maximum likelihood estimate of `p` from success counts out of `n` trials each.
+    pub fn fit(x: &Array1<T>, n: usize) -> Result<FitResult<T, Self>, ParamError> {
+        check_nonempty(x)?;
+        check_counts(x, T::zero(), T::from(n).unwrap())?;
+        let p = x.mean().unwrap() / T::from(n).unwrap();
+        Ok(FitResult::new(Self::new(n, p)?, x, true, 0))
+    }
+}
+
+impl<T: FloatConst> Distribution<T> for Binomial<T> {
+    fn mean(&self) -> T {
+        T::from(self.n).unwrap() * self.p
+    }
+
+    fn var(&self) -> T {
+        T::from(self.n).unwrap() * self.p * (T::one() - self.p)
+    }
+
+    fn mode(&self) -> Option<T> {
+        let n = T::from(self.n).unwrap();
+        let np = (n + T::one()) * self.p;
+        let m = np.floor();
+        if m == np && m > T::zero() && m <= n {
+            // Both m - 1 and m are modes.
+            None
+        } else if m > n {
+            Some(n)
+        } else {
+            Some(m)
+        }
+    }
+
+    fn entropy(&self) -> T {
+        discrete_entropy(self, 0, self.n)
+    }
+
+    fn skewness(&self) -> T {
+        let q = T::one() - self.p;
+        (q - self.p) / self.var().sqrt()
+    }
+
+    fn kurtosis(&self) -> T {
+        let pq = self.p * (T::one() - self.p);
+        (T::one() - T::from(6).unwrap() * pq) / self.var()
+    }
+
+    fn ln_pdf(&self, x: T) -> T {
+        discrete_ln_pdf(self, x)
+    }
+
+    fn cdf(&self, x: T) -> T {
+        let k = x.floor();
+        let n = T::from(self.n).unwrap();
+        if k < T::zero() {
+            T::zero()
+        } else if k >= n {
+            T::one()
+        } else {
+            beta_inc(n - k, k + T::one(), T::one() - self.p)
+        }
+    }
+
+    fn quantile(&self, p: T) -> T {
+        discrete_quantile(p, T::zero(), T::from(self.n).unwrap(), |k| self.cdf(k))
+    }
+
+    fn sample(&self, rng: &mut Rng) -> T {
+        if self.n < 64 {
+            T::from((0..self.n).filter(|_| rng.uniform::<T>() < self.p).count()).unwrap()
+        } else {
+            self.quantile(rng.uniform())
+        }
+    }
+}
+
+impl<T: FloatConst> Discrete<T> for Binomial<T> {
+    fn ln_pmf<S: Integer>(&self, k: S) -> T {
+        let n = T::from(self.n).unwrap();
+        let k = T::from(k).unwrap();
+        if k < T::zero() || k > n {
+            return T::neg_infinity();
+        }
+        let one = T::one();
+        ln_gamma(n + one) - ln_gamma(k + one) - ln_gamma(n - k + one) + xlny(k, self.p) + xlny(n - k, one - self.p)
+    }
+}
+
+pub struct Poisson<T: Float> {
+    lambda: T,
+}
+
+impl<T: FloatConst> Poisson<T> {
+    pub fn new(lambda: T) -> Result<Self, ParamError> {
+        check_positive("lambda", lambda)?;
+        Ok(Self { lambda })
+    }
+
+    pub fn lambda(&self) -> T {
+        self.lambda
+    }
+
+    /// Maximum likelihood estimate.
+    pub fn fit(x: &Array1<T>) -> Result<FitResult<T, Self>, ParamError> {
+        check_nonempty(x)?;
+        check_counts(x, T::zero(), T::infinity())?;
+        let lambda = x.mean().unwrap();
+        Ok(FitResult::new(Self::new(lambda)?, x, true, 0))
+    }
+}
+
+impl<T: FloatConst> Distribution<T> for Poisson<T> {
+    fn mean(&self) -> T {
+        self.lambda
+    }
+
+    fn var(&self) -> T {
+        self.lambda
+    }
+
+    fn mode(&self) -> Option<T> {
+        let m = self.lambda.floor();
+        if m == self.lambda && m > T::zero() {
+            // Both lambda - 1 and lambda are modes.
+            None
+        } else {
+            Some(m)
+        }
+    }
+
+    fn entropy(&self) -> T {
+        // The mass outside 40 standard deviations of the mean is negligible.
+        let width = T::from(40).unwrap() * (self.lambda.sqrt() + T::one());
+        let lo = (self.lambda - width).max(T::zero()).to_usize().unwrap();
+        let hi = (self.lambda + width).to_usize().unwrap();
+        discrete_entropy(self, lo, hi)
+    }
+
+    fn skewness(&self) -> T {
+        T::one() / self.lambda.sqrt()
+    }
+
+    fn kurtosis(&self) -> T {
+        T::one() / self.lambda
+    }
+
+    fn ln_pdf(&self, x: T) -> T {
+        discrete_ln_pdf(self, x)
+    }
+
+    fn cdf(&self, x: T) -> T {
+        let k = x.floor();
+        if k < T::zero() {
+            T::zero()
+        } else {
+            gamma_q(k + T::one(), self.lambda)
+        }
+    }
+
+    fn quantile(&self, p: T) -> T {
+        discrete_quantile(p, T::zero(), T::infinity(), |k| self.cdf(k))
+    }
+
+    fn sample(&self, rng: &mut Rng) -> T {
+        if self.lambda < T::from(30).unwrap() {
+            // Knuth's method: count uniforms until their product drops below exp(-lambda).
+            let l = (-self.lambda).exp();
+            let mut k = T::zero();
+            let mut prod = rng.uniform::<T>();
+            while prod > l {
+                k += T::one();
+                prod *= rng.uniform::<T>();
+            }
+            k
+        } else {
+            self.quantile(rng.uniform())
+        }
+    }
+}
+
+impl<T: FloatConst> Discrete<T> for Poisson<T> {
+    fn ln_pmf<S: Integer>(&self, k: S) -> T {
+        let k = T::from(k).unwrap();
+        if k < T::zero() {
+            return T::neg_infinity();
+        }
+        xlny(k, self.lambda) - self.lambda - ln_gamma(k + T::one())
+    }
+}
+
+impl<T: FloatConst> ExponentialFamily<T> for Poisson<T> {
+    fn natural_params(&self) -> Array1<T> {
+        arr1(&[self.lambda.ln()])
+    }
+
+    fn from_natural_params(eta: &Array1<T>) -> Result<Self, ParamError> {
+        check_len(eta, 1)?;
+        Self::new(eta[0].exp())
+    }
+
+    fn sufficient_stats(x: T) -> Array1<T> {
+        arr1(&[x])
+    }
+
+    fn ln_partition(eta: &Array1<T>) -> T {
+        eta[0].exp()
+    }
+
+    fn ln_base_measure(x: T) -> T {
+        if x >= T::zero() && x.fract() == T::zero() {
+            -ln_gamma(x + T::one())
+        } else {
+            T::neg_infinity()
+        }
+    }
+
+    fn mean_params(&self) -> Array1<T> {
+        arr1(&[self.lambda])
+    }
+
+    fn from_mean_params(m: &Array1<T>) -> Result<Self, ParamError> {
+        check_len(m, 1)?;
+        Self::new(m[0])
+    }
+}
+
+/// Distribution over the categories {0, 1, ..., K - 1} with probabilities `p`.
+pub struct Categorical<T: Float> {
+    p: Array1<T>,
+}
+
+impl<T: Float> Categorical<T> {
+    pub fn new(p: Array1<T>) -> Result<Self, ParamError> {
+        check_simplex("p", &p)?;
+        Ok(Self { p })
+    }
+
+    pub fn p(&self) -> &Array1<T> {
+        &self.p
+    }
+
+    /// Maximum likelihood estimate from observed category indices, with `k` categories.
+    pub fn fit(x: &Array1<T>, k: usize) -> Result<FitResult<T, Self>, ParamError> {
+        check_nonempty(x)?;
+        let mut p = Array1::<T>::zeros(k);
+        for &xi in x.iter() {
+            match xi.to_usize() {
+                Some(i) if i < k && T::from(i).unwrap() == xi => p[i] += T::one(),
+                _ => return Err(ParamError::OutOfRange("x")),
+            }
+        }
+        let n = T::from(x.len()).unwrap();
+        p.mapv_inplace(|e| e / n);
+        Ok(FitResult::new(Self { p }, x, true, 0))
+    }
+}
+
+impl<T: Float> Distribution<T> for Categorical<T> {
+    fn mean(&self) -> T {
+        self.p.iter().enumerate().map(|(k, &pk)| T::from(k).unwrap() * pk).sum()
+    }
+
+    fn var(&self) -> T {
+        let m = self.mean();
+        self.p.iter().enumerate().map(|(k, &pk)| {
+            let d = T::from(k).unwrap() - m;
+            d * d * pk
+        }).sum()
+    }
+
+    fn mode(&self) -> Option<T> {
+        let mut best = 0;
+        let mut tie = false;
+        for (k, &pk) in self.p.iter().enumerate().skip(1) {
+            if pk > self.p[best] {
+                best = k;
+                tie = false;
+            } else if pk == self.p[best] {
+                tie = true;
+            }
+        }
+        if tie {
+            None
+        } else {
+            Some(T::from(best).unwrap())
+        }
+    }
+
+    fn entropy(&self) -> T {
+        self.p.iter().map(|&pk| -xlny(pk, pk)).sum()
+    }
+
+    fn skewness(&self) -> T {
+        let m = self.mean();
+        let m3 = self.p.iter().enumerate().map(|(k, &pk)| (T::from(k).unwrap() - m).powi(3) * pk).sum::<T>();
+        m3 / self.var().powf(T::from(1.5).unwrap())
+    }
+
+    fn kurtosis(&self) -> T {
+        let m = self.mean();
+        let v = self.var();
+        let m4 = self.p.iter().enumerate().map(|(k, &pk)| (T::from(k).unwrap() - m).powi(4) * pk).sum::<T>();
+        m4 / (v * v) - T::from(3).unwrap()
+    }
+
+    fn ln_pdf(&self, x: T) -> T {
+        discrete_ln_pdf(self, x)
+    }
+
+    fn cdf(&self, x: T) -> T {
+        let k = x.floor();
+        if k < T::zero() {
+            return T::zero();
+        }
+        let k = k.to_usize().unwrap_or(usize::MAX);
+        self.p.iter().take(k.saturating_add(1)).fold(T::zero(), |acc, &pk| acc + pk)
+    }
+
+    fn quantile(&self, p: T) -> T {
+        let mut acc = T::zero();
+        for (k, &pk) in self.p.iter().enumerate() {
+            acc += pk;
+            if acc >= p {
+                return T::from(k).unwrap();
+            }
+        }
+        T::from(self.p.len() - 1).unwrap()
+    }
+
+    fn sample(&self, rng: &mut Rng) -> T {
+        self.quantile(rng.uniform())
+    }
+}
+
+impl<T: Float> Discrete<T> for Categorical<T> {
+    fn ln_pmf<S: Integer>(&self, k: S) -> T {
+        match k.to_usize() {
+            Some(k) if k < self.p.len() => self.p[k].ln(),
+            _ => T::neg_infinity(),
+        }
+    }
+}
+
+/// Counts of each category in `n` independent draws from a categorical distribution with probabilities `p`.
+pub struct Multinomial<T: Float> {
+    n: usize,
+    p: Array1<T>,
+}
+
+impl<T: FloatConst> Multinomial<T> {
+    pub fn new(n: usize, p: Array1<T>) -> Result<Self, ParamError> {
+        check_simplex("p", &p)?;
+        Ok(Self { n, p })
+    }
+
+    pub fn n(&self) -> usize {
+        self.n
+    }
+
+    pub fn p(&self) -> &Array1<T> {
+        &self.p
+    }
+
+    /// Maximum likelihood estimate from count vectors (n_samples x n_categories) of equal total.
+    pub fn fit<S: Integer>(x: &Array2<S>) -> Result<FitResult<T, Self>, ParamError> {
+        if x.nrows() == 0 {
+            return Err(ParamError::EmptyData);
+        }
+        let total_of = |xi: ArrayView1<S>| xi.iter().try_fold(0usize, |acc, &e| acc.checked_add(e.to_usize()?));
+        let n = total_of(x.row(0)).ok_or(ParamError::OutOfRange("x"))?;
+        if n == 0 || x.outer_iter().any(|xi| total_of(xi) != Some(n)) {
+            return Err(ParamError::OutOfRange("x"));
+        }
+        let counts = x.map(|&e| T::from(e).unwrap()).sum_axis(Axis(0));
+        let total = counts.sum();
+        let p = counts.map(|&e| e / total);
+        let dist = Self { n, p };
+        let ln_likelihood = x.outer_iter().map(|xi| dist.ln_pmf(&xi.to_owned())).sum();
+        Ok(FitResult { dist, ln_likelihood, converged: true, n_iter: 0 })
+    }
+
+    pub fn cov(&self) -> Array2<T> {
+        let k = self.p.len();
+        let n = T::from(self.n).unwrap();
+        let mut c = Array2::zeros((k, k));
+        for i in 0..k {
+            for j in 0..k {
+                c[[i, j]] = if i == j {
+                    n * self.p[i] * (T::one() - self.p[i])
+                } else {
+                    -n * self.p[i] * self.p[j]
+                };
+            }
+        }
+        c
+    }
+
+    pub fn ln_pmf<S: Integer>(&self, x: &Array1<S>) -> T {
+        let one = T::one();
+        let total = x.iter().fold(0, |acc, &e| acc + e.to_usize().unwrap_or(usize::MAX));
+        if total != self.n {
+            return T::neg_infinity();
+        }
+        x.iter().zip(self.p.iter()).fold(ln_gamma(T::from(self.n).unwrap() + one), |acc, (&xi, &pi)| {
+            let xi = T::from(xi).unwrap();
+            acc - ln_gamma(xi + one) + xlny(xi, pi)
+        })
+    }
+
+    pub fn pmf<S: Integer>(&self, x: &Array1<S>) -> T {
+        self.ln_pmf(x).exp()
+    }
+
+    /// Draws the counts one category at a time from the conditional binomials.
+    pub fn sample_int<S: Integer>(&self, rng: &mut Rng) -> Array1<S> {
+        let mut x = Array1::from_elem(self.p.len(), S::zero());
+        let mut n = self.n;
+        let mut rest = T::one();
+        for (xi, &pi) in x.iter_mut().zip(self.p.iter()) {
+            if n == 0 {
+                break;
+            }
+            let p = if rest > T::zero() { (pi / rest).min(T::one()) } else { T::one() };
+            let k = Binomial { n, p }.sample(rng).to_usize().unwrap();
+            *xi = S::from(k).unwrap();
+            n -= k;
+            rest -= pi;
+        }
+        x
+    }
+}
+
+impl<T: FloatConst> MultivariateDistribution<T> for Multinomial<T> {
+    type Value = Array1<T>;
+
+    fn mean(&self) -> Array1<T> {
+        let n = T::from(self.n).unwrap();
+        self.p.map(|&e| e * n)
+    }
+
+    fn var(&self) -> Array1<T> {
+        let n = T::from(self.n).unwrap();
+        self.p.map(|&e| n * e * (T::one() - e))
+    }
+
+    fn mode(&self) -> Option<Array1<T>> {
+        // Start from floor(n p) and hand out the remaining counts greedily by the largest pmf ratio.
+        let n = T::from(self.n).unwrap();
+        let mut x = self.p.map(|&e| (n * e).floor());
+        let mut rest = self.n - x.iter().fold(0, |acc, &e| acc + e.to_usize().unwrap());
+        while rest > 0 {
+            let mut best = 0;
+            let mut best_ratio = T::neg_infinity();
+            for (i, (&xi, &pi)) in x.iter().zip(self.p.iter()).enumerate() {
+                let ratio = pi / (xi + T::one());
+                if ratio > best_ratio {
+                    best = i;
+                    best_ratio = ratio;
+                }
+            }
+            x[best] += T::one();
+            rest -= 1;
+        }
+        // The log-pmf is separable and concave on the simplex, so the mode is unique unless moving one count from
+        // some category i to some j leaves the pmf unchanged, i.e. p_j x_i = p_i (x_j + 1).
+        let tol = T::from(4).unwrap() * T::epsilon();
+        for (i, (&xi, &pi)) in x.iter().zip(self.p.iter()).enumerate() {
+            if xi == T::zero() {
+                continue;
+            }
+            for (j, (&xj, &pj)) in x.iter().zip(self.p.iter()).enumerate() {
+                let (moved, kept) = (pj * xi, pi * (xj + T::one()));
+                if i != j && (moved - kept).abs() <= tol * kept {
+                    return None;
+                }
+            }
+        }
+        Some(x)
+    }
+
+    fn ln_pdf(&self, x: &Array1<T>) -> T {
+        if x.iter().any(|&e| e < T::zero() || e.fract() != T::zero()) {
+            return T::neg_infinity();
+        }
+        self.ln_pmf(&x.map(|&e| e.to_usize().unwrap()))
+    }
+
+    fn sample(&self, rng: &mut Rng) -> Array1<T> {
+        self.sample_int::<usize>(rng).map(|&e| T::from(e).unwrap())
+    }
+}
+
+/// Number of Bernoulli trials up to and including the first success, supported on {1, 2, ...}.
+pub struct Geometric<T: Float> {
+    p: T,
+}
+
+impl<T: Float> Geometric<T> {
+    pub fn new(p: T) -> Result<Self, ParamError> {
+        check_finite("p", p)?;
+        if p > T::zero() && p <= T::one() {
+            Ok(Self { p })
+        } else {
+            Err(ParamError::OutOfRange("p"))
+        }
+    }
+
+    pub fn p(&self) -> T {
+        self.p
+    }
+
+    /// Maximum likelihood estimate.
+    pub fn fit(x: &Array1<T>) -> Result<FitResult<T, Self>, ParamError> {
+        check_nonempty(x)?;
+        check_counts(x, T::one(), T::infinity())?;
+        let p = T::one() / x.mean().unwrap();
+        Ok(FitResult::new(Self::new(p)?, x, true, 0))
+    }
+}
+
+impl<T: Float> Distribution<T> for Geometric<T> {
+    fn mean(&self) -> T {
+        T::one() / self.p
+    }
+
+    fn var(&self) -> T {
+        (T::one() - self.p) / (self.p * self.p)
+    }
+
+    fn mode(&self) -> Option<T> {
+        Some(T::one())
+    }
+
+    fn entropy(&self) -> T {
+        let q = T::one() - self.p;
+        (-xlny(q, q) - xlny(self.p, self.p)) / self.p
+    }
+
+    fn skewness(&self) -> T {
+        (T::from(2).unwrap() - self.p) / (T::one() - self.p).sqrt()
+    }
+
+    fn kurtosis(&self) -> T {
+        T::from(6).unwrap() + self.p * self.p / (T::one() - self.p)
+    }
+
+    fn ln_pdf(&self, x: T) -> T {
+        discrete_ln_pdf(self, x)
+    }
+
+    fn cdf(&self, x: T) -> T {
+        let k = x.floor();
+        if k < T::one() {
+            T::zero()
+        } else {
+            -(k * (-self.p).ln_1p()).exp_m1()
+        }
+    }
+
+    fn quantile(&self, p: T) -> T {
+        let one = T::one();
+        if self.p == one {
+            return one;
+        }
+        let k = ((-p).ln_1p() / (-self.p).ln_1p()).ceil();
+        if k <= one {
+            return one;
+        }
+        // The closed form can land one off when p is (up to rounding) a value of the CDF.
+        if self.cdf(k - one) >= p {
+            k - one
+        } else if self.cdf(k) < p {
+            k + one
+        } else {
+            k
+        }
+    }
+
+    fn sample(&self, rng: &mut Rng) -> T {
+        self.quantile(rng.uniform())
+    }
+}
+
+impl<T: Float> Discrete<T> for Geometric<T> {
+    fn ln_pmf<S: Integer>(&self, k: S) -> T {
+        let k = T::from(k).unwrap();
+        if k < T::one() {
+            return T::neg_infinity();
+        }
+        xlny(k - T::one(), T::one() - self.p) + self.p.ln()
+    }
+}
+
+/// Multivariate Gaussian distribution, parametrized by its mean and covariance.
+pub struct MultivariateGaussian<T: Float> {
+    mu: Array1<T>,
+    sigma: Array2<T>,
+    /// Lower Cholesky factor of `sigma`
+    l: Array2<T>,
+}
+
+impl<T: FloatConst> MultivariateGaussian<T> {
+    pub fn new(mu: Array1<T>, sigma: Array2<T>) -> Result<Self, ParamError> {
+        if sigma.shape() != [mu.len(), mu.len()] {
+            return Err(ParamError::ShapeMismatch);
+        }
+        for &m in mu.iter() {
+            check_finite("mu", m)?;
+        }
+        match sigma.cholesky(UPLO::Lower) {
+            Ok(l) => Ok(Self { mu, sigma, l }),
+            Err(_) => Err(ParamError::NotPositiveDefinite("sigma")),
+        }
+    }
+
+    pub fn dim(&self) -> usize {
+        self.mu.len()
+    }
+
+    pub fn cov(&self) -> &Array2<T> {
+        &self.sigma
+    }
+
+    /// L^{-1} (x - mu), where sigma = L L^T
+    fn whiten(&self, x: &Array1<T>) -> Array1<T> {
+        self.l.solve_triangular(UPLO::Lower, Diag::NonUnit, &(x - &self.mu)).unwrap()
+    }
+
+    pub fn mahalanobis(&self, x: &Array1<T>) -> T {
+        let z = self.whiten(x);
+        z.dot(&z).sqrt()
+    }
+
+    /// n_samples x dim
+    pub fn sample_n(&self, rng: &mut Rng, n: usize) -> Array2<T> {
+        let d = self.dim();
+        let mut x = Array2::zeros((n, d));
+        for mut row in x.outer_iter_mut() {
+            row.assign(&self.sample(rng));
+        }
+        x
+    }
+
+    /// Marginal distribution of the dimensions in `index`.
+    pub fn marginal(&self, index: &[usize]) -> Self {
+        let mu = self.mu.select(Axis(0), index);
+        let sigma = self.sigma.select(Axis(0), index).select(Axis(1), index);
+        // A principal submatrix of a positive definite matrix is positive definite.
+        Self::new(mu, sigma).unwrap()
+    }
+
+    /// Distribution of the remaining dimensions given that the dimensions in `index` equal `value`.
+    pub fn conditional(&self, index: &[usize], value: &Array1<T>) -> Result<Self, ParamError> {
+        let rest = (0..self.dim()).filter(|i| !index.contains(i)).collect::<Vec<usize>>();
+        let s_aa = self.sigma.select(Axis(0), &rest).select(Axis(1), &rest);
+        let s_ab = self.sigma.select(Axis(0), &rest).select(Axis(1), index);
+        let s_bb = self.sigma.select(Axis(0), index).select(Axis(1), index);
+        let k = s_ab.dot(&s_bb.invc().unwrap());
+        let mu = self.mu.select(Axis(0), &rest) + k.dot(&(value - &self.mu.select(Axis(0), index)));
+        let sigma = s_aa - k.dot(&s_ab.t());
+        Self::new(mu, sigma)
+    }
+}
+
+impl<T: FloatConst> MultivariateDistribution<T> for MultivariateGaussian<T> {
+    type Value = Array1<T>;
+
+    fn mean(&self) -> Array1<T> {
+        self.mu.clone()
+    }
+
+    fn var(&self) -> Array1<T> {
+        self.sigma.diag().to_owned()
+    }
+
+    fn mode(&self) -> Option<Array1<T>> {
+        Some(self.mu.clone())
+    }
+
+    fn ln_pdf(&self, x: &Array1<T>) -> T {
+        let z = self.whiten(x);
+        let d = T::from(self.dim()).unwrap();
+        let half_ln_det = self.l.diag().iter().map(|&e| e.ln()).sum::<T>();
+        -d * T::LN_TWO_PI_SQRT - half_ln_det - T::from(0.5).unwrap() * z.dot(&z)
+    }
+
+    fn sample(&self, rng: &mut Rng) -> Array1<T> {
+        let z = (0..self.dim()).map(|_| rng.normal()).collect::<Array1<T>>();
+        &self.mu + &self.l.dot(&z)
+    }
+}
+
+/// Covariance structure of the components of a `GaussianMixture`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CovarianceType {
+    /// Each component has its own covariance matrix
+    Full,
+    /// Each component has its own diagonal covariance matrix
+    Diagonal,
+    /// Each component has its own variance, the same in every dimension
+    Spherical,
+    /// All components share one covariance matrix
+    Tied,
+}
+
+/// Mixture of multivariate Gaussians with weights `weights`.
+pub struct GaussianMixture<T: Float> {
+    weights: Array1<T>,
+    components: Vec<MultivariateGaussian<T>>,
+    covariance_type: CovarianceType,
+}
+
+/// ln sum exp(v), without overflow.
+fn ln_sum_exp<T: Float>(v: ArrayView1<T>) -> T {
+    let m = v.fold(T::neg_infinity(), |m, &x| m.max(x));
+    if m == T::neg_infinity() {
+        return m;
+    }
+    m + v.fold(T::zero(), |a, &x| a + (x - m).exp()).ln()
+}
+
+/// Index drawn with probability proportional to the non-negative `w`.
+pub(crate) fn sample_index<T: Float>(rng: &mut Rng, w: &Array1<T>) -> usize {
+    let u = rng.uniform::<T>() * w.sum();
+    let mut acc = T::zero();
+    for (i, &wi) in w.iter().enumerate() {
+        acc += wi;
+        if u < acc {
+            return i;
+        }
+    }
+    // Rounding can leave u just above the total.
+    w.iter().rposition(|&wi| wi > T::zero()).unwrap()
+}
+
+impl<T: FloatConst> GaussianMixture<T> {
+    /// Mixture of `components` with full covariance. `weights` must sum to one.
+    pub fn new(weights: Array1<T>, components: Vec<MultivariateGaussian<T>>) -> Result<Self, ParamError> {
+        if components.is_empty() || weights.len() != components.len() {
+            return Err(ParamError::ShapeMismatch);
+        }
+        let d = components[0].dim();
+        if components.iter().any(|c| c.dim() != d) {
+            return Err(ParamError::ShapeMismatch);
+        }
+        check_simplex("weights", &weights)?;
+        Ok(Self { weights, components, covariance_type: CovarianceType::Full })
+    }
+
+    pub fn weights(&self) -> &Array1<T> {
+        &self.weights
+    }
+
+    pub fn components(&self) -> &[MultivariateGaussian<T>] {
+        &self.components
+    }
+
+    pub fn covariance_type(&self) -> CovarianceType {
+        self.covariance_type
+    }
+
+    pub fn n_components(&self) -> usize {
+        self.components.len()
+    }
+
+    pub fn dim(&self) -> usize {
+        self.components[0].dim()
+    }
+
+    /// Covariance matrix of the mixture.
+    pub fn cov(&self) -> Array2<T> {
+        let mean = self.mean();
+        let d = self.dim();
+        let mut c = Array2::zeros((d, d));
+        for (&w, comp) in self.weights.iter().zip(self.components.iter()) {
+            let dm = &comp.mean() - &mean;
+            for ((i, j), cij) in c.indexed_iter_mut() {
+                *cij += w * (comp.cov()[[i, j]] + dm[i] * dm[j]);
+            }
+        }
+        c
+    }
+
+    /// Maximum likelihood estimate with `k` components by EM, initialized from k-means++ seeded k-means.
+    ///
+    /// Iterates until the mean log-likelihood per sample improves by less than 1e-6, for at most 500 iterations.
+    /// 1e-6 times the mean variance of the features is added to the diagonal of every covariance so that collapsing
+    /// components stay positive definite. Fails unless 1 <= k <= n_samples, or when a covariance is still singular,
+    /// as for data without variance.
+    pub fn fit(
+        x: &Array2<T>,
+        k: usize,
+        covariance_type: CovarianceType,
+        rng: &mut Rng,
+    ) -> Result<FitResult<T, Self>, ParamError> {
+        let n = x.nrows();
+        if n == 0 {
+            return Err(ParamError::EmptyData);
+        }
+        if k < 1 || k > n {
+            return Err(ParamError::OutOfRange("k"));
+        }
+        let reg = T::from(1e-6).unwrap() * crate::stats::var(x, 0).mean().unwrap();
+        let mut resp = Array2::zeros((n, k));
+        for (i, &j) in kmeans(x, k, rng).iter().enumerate() {
+            resp[[i, j]] = T::one();
+        }
+        let tol = T::from(1e-6).unwrap();
+        let nf = T::from(n).unwrap();
+        let mut dist = Self::maximize(x, &resp, covariance_type, reg)?;
+        let mut prev = T::neg_infinity();
+        let mut converged = false;
+        let mut n_iter = 0;
+        while n_iter < 500 {
+            n_iter += 1;
+            let (r, ll) = dist.responsibilities(x);
+            dist = Self::maximize(x, &r, covariance_type, reg)?;
+            if (ll - prev) / nf < tol {
+                converged = true;
+                break;
+            }
+            prev = ll;
+        }
+        let ln_likelihood = dist.ln_likelihood(x);
+        Ok(FitResult { dist, ln_likelihood, converged, n_iter })
+    }
+
+    /// M step: weights, means and covariances from the responsibilities `resp` (n_samples x n_components), with
+    /// `reg` added to the diagonal of each covariance.
+    fn maximize(x: &Array2<T>, resp: &Array2<T>, covariance_type: CovarianceType, reg: T) -> Result<Self, ParamError> {
+        let d = x.ncols();
+        let k = resp.ncols();
+        // A tiny floor keeps empty components from dividing by zero.
+        let nk = resp.sum_axis(Axis(0)).map(|&v| v + T::from(10).unwrap() * T::epsilon());
+        let means = resp.t().dot(x);
+        let mut covs = Vec::with_capacity(k);
+        for j in 0..k {
+            let mean = means.row(j).map(|&v| v / nk[j]);
+            let xc = x - &mean;
+            let xw = &xc * &resp.column(j).map(|&r| r.sqrt()).insert_axis(Axis(1));
+            let cov = xw.t().dot(&xw).map(|&c| c / nk[j]);
+            covs.push((mean, cov));
+        }
+        if covariance_type == CovarianceType::Tied {
+            let mut tied = Array2::<T>::zeros((d, d));
+            for (j, (_, cov)) in covs.iter().enumerate() {
+                tied = tied + cov.map(|&c| c * nk[j]);
+            }
+            let total = nk.sum();
+            let tied = tied.map(|&c| c / total);
+            for (_, cov) in covs.iter_mut() {
+                cov.assign(&tied);
+            }
+        }
+        let df = T::from(d).unwrap();
+        let components = covs
+            .into_iter()
+            .map(|(mean, cov)| {
+                let mut cov = match covariance_type {
+                    CovarianceType::Full | CovarianceType::Tied => cov,
+                    CovarianceType::Diagonal => Array2::from_diag(&cov.diag()),
+                    CovarianceType::Spherical => {
+                        let v = cov.diag().sum() / df;
+                        Array2::from_diag(&Array1::from_elem(d, v))
+                    }
+                };
+                cov.diag_mut().map_inplace(|v| *v += reg);
+                MultivariateGaussian::new(mean, cov)
+            })
+            .collect::<Result<_, _>>()?;
+        let total = nk.sum();
+        let weights = nk.map(|&v| v / total);
+        Ok(Self { weights, components, covariance_type })
+    }
+
+    /// ln (weight_j N(x_i | component j)), n_samples x n_components.
+    fn ln_joint(&self, x: &Array2<T>) -> Array2<T> {
+        let mut lj = Array2::zeros((x.nrows(), self.n_components()));
+        for (i, row) in x.genrows().into_iter().enumerate() {
+            let row = row.to_owned();
+            for (j, comp) in self.components.iter().enumerate() {
+                lj[[i, j]] = self.weights[j].ln() + comp.ln_pdf(&row);
+            }
+        }
+        lj
+    }
+
+    /// E step: posterior probabilities of the components, and the log-likelihood of `x`.
+    fn responsibilities(&self, x: &Array2<T>) -> (Array2<T>, T) {
+        let mut lj = self.ln_joint(x);
+        let mut ll = T::zero();
+        for mut row in lj.genrows_mut() {
+            let lse = ln_sum_exp(row.view());
+            ll += lse;
+            row.map_inplace(|v| *v = (*v - lse).exp());
+        }
+        (lj, ll)
+    }
+
+    /// Posterior probability of each component for each row of `x`, n_samples x n_components.
+    pub fn predict_proba(&self, x: &Array2<T>) -> Array2<T> {
+        self.responsibilities(x).0
+    }
+
+    /// Most probable component for each row of `x`.
+    pub fn predict(&self, x: &Array2<T>) -> Array1<usize> {
+        self.ln_joint(x)
+            .genrows()
+            .into_iter()
+            .map(|row| {
+                let mut best = 0;
+                for (j, &v) in row.iter().enumerate() {
+                    if v > row[best] {
+                        best = j;
+                    }
+                }
+                best
+            })
+            .collect()
+    }
+
+    /// Log density at each row of `x`.
+    pub fn score_samples(&self, x: &Array2<T>) -> Array1<T> {
+        self.ln_joint(x).genrows().into_iter().map(ln_sum_exp).collect()
+    }
+
+    pub fn ln_likelihood(&self, x: &Array2<T>) -> T {
+        self.score_samples(x).sum()
+    }
+
+    /// Number of free parameters: weights, means and covariances.
+    pub fn n_params(&self) -> usize {
+        let (k, d) = (self.n_components(), self.dim());
+        let cov = match self.covariance_type {
+            CovarianceType::Full => k * d * (d + 1) / 2,
+            CovarianceType::Diagonal => k * d,
+            CovarianceType::Spherical => k,
+            CovarianceType::Tied => d * (d + 1) / 2,
+        };
+        k - 1 + k * d + cov
+    }
+
+    /// Bayesian information criterion on `x`; lower is better.
+    pub fn bic(&self, x: &Array2<T>) -> T {
+        let p = T::from(self.n_params()).unwrap();
+        let n = T::from(x.nrows()).unwrap();
+        -T::from(2).unwrap() * self.ln_likelihood(x) + p * n.ln()
+    }
+
+    /// Akaike information criterion on `x`; lower is better.
+    pub fn aic(&self, x: &Array2<T>) -> T {
+        let two = T::from(2).unwrap();
+        let p = T::from(self.n_params()).unwrap();
+        -two * self.ln_likelihood(x) + two * p
+    }
+
+    /// n_samples x dim, with the component each sample was drawn from.
+    pub fn sample_n(&self, rng: &mut Rng, n: usize) -> (Array2<T>, Array1<usize>) {
+        let mut x = Array2::zeros((n, self.dim()));
+        let mut label = Array1::zeros(n);
+        for (mut row, l) in x.outer_iter_mut().zip(label.iter_mut()) {
+            *l = sample_index(rng, &self.weights);
+            row.assign(&self.components[*l].sample(rng));
+        }
+        (x, label)
+    }
+}
+
+impl<T: FloatConst> MultivariateDistribution<T> for GaussianMixture<T> {
+    type Value = Array1<T>;
+
+    fn mean(&self) -> Array1<T> {
+        let mut m = Array1::zeros(self.dim());
+        for (&w, comp) in self.weights.iter().zip(self.components.iter()) {
+            m = m + comp.mean().map(|&v| v * w);
+        }
+        m
+    }
+
+    fn var(&self) -> Array1<T> {
+        self.cov().diag().to_owned()
+    }
+
+    /// The highest mode found by the fixed-point iteration of Carreira-Perpinan started from each component mean.
+    fn mode(&self) -> Option<Array1<T>> {
+        let d = self.dim();
+        let precisions: Vec<Array2<T>> = self.components.iter().map(|c| c.cov().invc().unwrap()).collect();
+        let tol = T::epsilon().sqrt();
+        let mut best: Option<(Array1<T>, T)> = None;
+        for start in self.components.iter() {
+            let mut x = start.mean();
+            for _ in 0..1000 {
+                let lj: Array1<T> = self
+                    .components
+                    .iter()
+                    .zip(self.weights.iter())
+                    .map(|(c, &w)| w.ln() + c.ln_pdf(&x))
+                    .collect();
+                let lse = ln_sum_exp(lj.view());
+                let mut a = Array2::zeros((d, d));
+                let mut b = Array1::zeros(d);
+                for (j, p) in precisions.iter().enumerate() {
+                    let r = (lj[j] - lse).exp();
+                    a = a + p.map(|&v| v * r);
+                    b = b + p.dot(&self.components[j].mean()).map(|&v| v * r);
+                }
+                let next: Array1<T> = a.solve(&b).unwrap();
+                let step = (&next - &x).fold(T::zero(), |m, &v| m.max(v.abs()));
+                let scale = next.fold(T::one(), |m, &v| m.max(v.abs()));
+                x = next;
+                if step <= tol * scale {
+                    break;
+                }
+            }
+            let lp = self.ln_pdf(&x);
+            let better = match best {
+                Some((_, b)) => lp > b,
+                None => true,
+            };
+            if better {
+                best = Some((x, lp));
+            }
+        }
+        best.map(|(x, _)| x)
+    }
+
+    fn ln_pdf(&self, x: &Array1<T>) -> T {
+        let lj: Array1<T> =
+            self.components.iter().zip(self.weights.iter()).map(|(c, &w)| w.ln() + c.ln_pdf(x)).collect();
+        ln_sum_exp(lj.view())
+    }
+
+    fn sample(&self, rng: &mut Rng) -> Array1<T> {
+        self.components[sample_index(rng, &self.weights)].sample(rng)
+    }
+}
+
+/// Prior of a `BayesianGaussianMixture`: Dirichlet(alpha0) on the weights and, for every component, a
+/// Gaussian-Wishart N(mu | m0, (beta0 Lambda)^-1) W(Lambda | w0, nu0) on the mean and precision.
+pub struct MixturePrior<T: Float> {
+    alpha0: T,
+    beta0: T,
+    m0: Array1<T>,
+    nu0: T,
+    w0: Array2<T>,
+}
+
+impl<T: FloatConst> MixturePrior<T> {
+    /// Fails unless alpha0 and beta0 are positive, nu0 > dim - 1 and `w0` is a dim x dim positive definite matrix,
+    /// with dim the length of `m0`.
+    pub fn new(alpha0: T, beta0: T, m0: Array1<T>, nu0: T, w0: Array2<T>) -> Result<Self, ParamError> {
+        check_positive("alpha0", alpha0)?;
+        check_positive("beta0", beta0)?;
+        for &v in m0.iter() {
+            check_finite("m0", v)?;
+        }
+        if w0.shape() != [m0.len(), m0.len()] {
+            return Err(ParamError::ShapeMismatch);
+        }
+        check_finite("nu0", nu0)?;
+        if nu0 <= T::from(m0.len()).unwrap() - T::one() {
+            return Err(ParamError::OutOfRange("nu0"));
+        }
+        ln_det_cholesky(&w0).ok_or(ParamError::NotPositiveDefinite("w0"))?;
+        Ok(Self { alpha0, beta0, m0, nu0, w0 })
+    }
+
+    /// Weakly informative prior centred on the data: alpha0 = 1e-3, so that unneeded components empty out,
+    /// beta0 = 1, m0 the sample mean, nu0 = dim and w0 such that the prior mean of each precision is the inverse of
+    /// the sample covariance. Fails with fewer than two samples or when the sample covariance is singular.
+    pub fn from_data(x: &Array2<T>) -> Result<Self, ParamError> {
+        if x.nrows() < 2 {
+            return Err(ParamError::EmptyData);
+        }
+        let d = x.ncols();
+        let nu0 = T::from(d).unwrap();
+        let cov_inv = crate::stats::cov(x, 1).invc().map_err(|_| ParamError::NotPositiveDefinite("w0"))?;
+        let w0 = cov_inv.map(|&v| v / nu0);
+        Self::new(T::from(1e-3).unwrap(), T::one(), crate::stats::mean(x), nu0, w0)
+    }
+
+    /// Dirichlet concentration of the weights.
+    pub fn alpha0(&self) -> T {
+        self.alpha0
+    }
+
+    pub fn beta0(&self) -> T {
+        self.beta0
+    }
+
+    pub fn m0(&self) -> &Array1<T> {
+        &self.m0
+    }
+
+    pub fn nu0(&self) -> T {
+        self.nu0
+    }
+
+    pub fn w0(&self) -> &Array2<T> {
+        &self.w0
+    }
+}
+
+/// Variational Bayesian Gaussian mixture (Bishop, PRML 10.2).
+///
+/// The posterior factorizes into Dirichlet(alpha) on the weights and, for component k, the Gaussian-Wishart
+/// N(mu_k | m_k, (beta_k Lambda_k)^-1) W(Lambda_k | w_k, nu_k). Components whose expected number of samples
+/// falls below one are removed during fitting.
+pub struct BayesianGaussianMixture<T: Float> {
+    alpha: Array1<T>,
+    beta: Array1<T>,
+    /// n_components x dim
+    m: Array2<T>,
+    nu: Array1<T>,
+    w: Vec<Array2<T>>,
+    /// ln |w_k|
+    ln_det_w: Array1<T>,
+    elbo: Vec<T>,
+    converged: bool,
+}
+
+/// Expected sufficient statistics of the components under the responsibilities `r`: counts N_k, means
+/// x_bar_k and covariances S_k (PRML 10.51-10.53).
+fn weighted_stats<T: Float>(x: &Array2<T>, r: &Array2<T>) -> (Array1<T>, Array2<T>, Vec<Array2<T>>) {
+    // A tiny floor keeps empty components from dividing by zero.
+    let nk = r.sum_axis(Axis(0)).map(|&v| v + T::from(10).unwrap() * T::epsilon());
+    let mut xbar = r.t().dot(x);
+    let mut s = Vec::with_capacity(r.ncols());
+    for (j, mut xb) in xbar.genrows_mut().into_iter().enumerate() {
+        xb.map_inplace(|v| *v /= nk[j]);
+        let xw = (x - &xb) * &r.column(j).map(|&v| v.sqrt()).insert_axis(Axis(1));
+        s.push(xw.t().dot(&xw).map(|&v| v / nk[j]));
+    }
+    (nk, xbar, s)
+}
+
+impl<T: FloatConst> BayesianGaussianMixture<T> {
+    /// Fits with at most `k` components by coordinate ascent on the evidence lower bound, starting from k-means++
+    /// seeded k-means.
+    ///
+    /// Iterates until the ELBO per sample improves by less than 1e-6, for at most 500 iterations. Fails unless
+    /// 1 <= k <= n_samples and the prior has the dimension of the data, or when an updated scale matrix is not
+    /// positive definite.
+    pub fn fit(x: &Array2<T>, k: usize, prior: &MixturePrior<T>, rng: &mut Rng) -> Result<Self, ParamError> {
+        let n = x.nrows();
+        if n == 0 {
+            return Err(ParamError::EmptyData);
+        }
+        if prior.m0.len() != x.ncols() {
+            return Err(ParamError::ShapeMismatch);
+        }
+        if k < 1 || k > n {
+            return Err(ParamError::OutOfRange("k"));
+        }
+        let mut r = Array2::zeros((n, k));
+        for (i, &j) in kmeans(x, k, rng).iter().enumerate() {
+            r[[i, j]] = T::one();
+        }
+        let nf = T::from(n).unwrap();
+        let tol = T::from(1e-6).unwrap();
+        let mut vb = Self::maximize(x, &r, prior, Vec::new())?;
+        let mut pruned = false;
+        while vb.elbo.len() < 500 {
+            let elbo = vb.lower_bound(x, &r, prior)?;
+            let prev = vb.elbo.last().cloned();
+            vb.elbo.push(elbo);
+            if let (Some(prev), false) = (prev, pruned) {
+                if (elbo - prev) / nf < tol {
+                    vb.converged = true;
+                    break;
+                }
+            }
+            r = vb.responsibilities(x).0;
+            let nk = r.sum_axis(Axis(0));
+            let keep: Vec<usize> = (0..nk.len()).filter(|&j| nk[j] >= T::one()).collect();
+            pruned = !keep.is_empty() && keep.len() < nk.len();
+            if pruned {
+                r = r.select(Axis(1), &keep);
+                for mut row in r.genrows_mut() {
+                    let total = row.sum();
+                    row.map_inplace(|v| *v /= total);
+                }
+            }
+            vb = Self::maximize(x, &r, prior, vb.elbo)?;
+        }
+        Ok(vb)
+    }
+
+    /// Updates q(pi, mu, Lambda) from the responsibilities `r` (PRML 10.58, 10.60-10.63).
+    fn maximize(x: &Array2<T>, r: &Array2<T>, prior: &MixturePrior<T>, elbo: Vec<T>) -> Result<Self, ParamError> {
+        let (nk, xbar, s) = weighted_stats(x, r);
+        let w0_inv = prior.w0.invc().map_err(|_| ParamError::NotPositiveDefinite("w0"))?;
+        let beta = nk.map(|&v| prior.beta0 + v);
+        let mut m = Array2::zeros(xbar.dim());
+        let mut w = Vec::with_capacity(nk.len());
+        let mut ln_det_w = Array1::zeros(nk.len());
+        for j in 0..nk.len() {
+            let b0 = prior.beta0;
+            m.row_mut(j).assign(&(prior.m0.map(|&v| v * b0) + xbar.row(j).map(|&v| v * nk[j])).map(|&v| v / beta[j]));
+            let dx = &xbar.row(j) - &prior.m0;
+            let c = b0 * nk[j] / (b0 + nk[j]);
+            let mut w_inv = &w0_inv + &s[j].map(|&v| v * nk[j]);
+            for ((a, b), e) in w_inv.indexed_iter_mut() {
+                *e += c * dx[a] * dx[b];
+            }
+            let wj = w_inv.invc().map_err(|_| ParamError::NotPositiveDefinite("w"))?;
+            ln_det_w[j] = ln_det_cholesky(&wj).ok_or(ParamError::NotPositiveDefinite("w"))?.0;
+            w.push(wj);
+        }
+        Ok(Self {
+            alpha: nk.map(|&v| prior.alpha0 + v),
+            beta,
+            m,
+            nu: nk.map(|&v| prior.nu0 + v),
+            w,
+            ln_det_w,
+            elbo,
+            converged: false,
+        })
+    }
+
+    /// Dirichlet concentration of the weights.
+    pub fn alpha(&self) -> &Array1<T> {
+        &self.alpha
+    }
+
+    pub fn beta(&self) -> &Array1<T> {
+        &self.beta
+    }
+
+    /// Posterior means of the component means, n_components x dim.
+    pub fn m(&self) -> &Array2<T> {
+        &self.m
+    }
+
+    pub fn nu(&self) -> &Array1<T> {
+        &self.nu
+    }
+
+    /// Wishart scale matrix of each component.
+    pub fn w(&self) -> &[Array2<T>] {
+        &self.w
+    }
+
+    /// Evidence lower bound after each iteration. It never decreases, except when components are pruned.
+    pub fn elbo(&self) -> &[T] {
+        &self.elbo
+    }
+
+    pub fn converged(&self) -> bool {
+        self.converged
+    }
+
+    pub fn n_components(&self) -> usize {
+        self.alpha.len()
+    }
+
+    pub fn dim(&self) -> usize {
+        self.m.ncols()
+    }
+
+    /// E[ln |Lambda_k|] for each component (PRML 10.65).
+    fn ln_det_precision(&self) -> Array1<T> {
+        let d = self.dim();
+        let half = T::from(0.5).unwrap();
+        let ln_2 = T::from(std::f64::consts::LN_2).unwrap();
+        Zip::from(&self.nu).and(&self.ln_det_w).apply_collect(|&nu, &ld| {
+            (0..d).fold(T::from(d).unwrap() * ln_2 + ld, |acc, i| acc + digamma(half * (nu - T::from(i).unwrap())))
+        })
+    }
+
+    /// (x_i - m_k)^T w_k (x_i - m_k) for every row of `x` and component, n_samples x n_components.
+    fn quad_form(&self, x: &Array2<T>) -> Array2<T> {
+        let mut q = Array2::zeros((x.nrows(), self.n_components()));
+        for (j, w) in self.w.iter().enumerate() {
+            let xc = x - &self.m.row(j);
+            let v = (&xc.dot(w) * &xc).sum_axis(Axis(1));
+            q.column_mut(j).assign(&v);
+        }
+        q
+    }
+
+    /// Responsibilities r_nk and ln rho_nk (PRML 10.46, 10.49).
+    fn responsibilities(&self, x: &Array2<T>) -> (Array2<T>, Array2<T>) {
+        let d = T::from(self.dim()).unwrap();
+        let half = T::from(0.5).unwrap();
+        let e_ln_det = self.ln_det_precision();
+        let psi_total = digamma(self.alpha.sum());
+        let mut ln_rho = self.quad_form(x);
+        for (j, mut col) in ln_rho.gencolumns_mut().into_iter().enumerate() {
+            let c = digamma(self.alpha[j]) - psi_total + half * e_ln_det[j] - d * T::LN_TWO_PI_SQRT
+                - half * d / self.beta[j];
+            let nu = self.nu[j];
+            col.map_inplace(|q| *q = c - half * nu * *q);
+        }
+        let mut r = ln_rho.clone();
+        for mut row in r.genrows_mut() {
+            let lse = ln_sum_exp(row.view());
+            row.map_inplace(|v| *v = (*v - lse).exp());
+        }
+        (r, ln_rho)
+    }
+
+    /// Evidence lower bound for the responsibilities `r` that produced this posterior (PRML 10.70-10.77).
+    fn lower_bound(&self, x: &Array2<T>, r: &Array2<T>, prior: &MixturePrior<T>) -> Result<T, ParamError> {
+        let dim = self.dim();
+        let d = T::from(dim).unwrap();
+        let k = T::from(self.n_components()).unwrap();
+        let half = T::from(0.5).unwrap();
+        let ln_2 = T::from(std::f64::consts::LN_2).unwrap();
+        let ln_2pi = T::from(2).unwrap() * T::LN_TWO_PI_SQRT;
+        let (nk, xbar, s) = weighted_stats(x, r);
+        let e_ln_det = self.ln_det_precision();
+        let ln_det_w = &self.ln_det_w;
+        let psi_total = digamma(self.alpha.sum());
+        let e_ln_pi = self.alpha.map(|&a| digamma(a) - psi_total);
+        let w0_inv = prior.w0.invc().map_err(|_| ParamError::NotPositiveDefinite("w0"))?;
+        let ln_det_w0 = ln_det_cholesky(&prior.w0).ok_or(ParamError::NotPositiveDefinite("w0"))?.0;
+        // ln B(w, nu) of the Wishart normalizer (PRML B.79)
+        let ln_b = |ld: T, nu: T| -half * nu * ld - (half * nu * d * ln_2 + ln_gamma_multi(half * nu, dim));
+        let ln_c = |a: &Array1<T>| ln_gamma(a.sum()) - a.fold(T::zero(), |acc, &v| acc + ln_gamma(v));
+
+        let mut ln_p_x = T::zero();
+        let mut ln_p_mu = T::zero();
+        let mut ln_q_mu = T::zero();
+        for j in 0..self.n_components() {
+            let w = &self.w[j];
+            let (nu, beta) = (self.nu[j], self.beta[j]);
+            let dx = &xbar.row(j) - &self.m.row(j);
+            let dm = &self.m.row(j) - &prior.m0;
+            let tr_sw = (&s[j] * w).sum();
+            ln_p_x += half * nk[j] * (e_ln_det[j] - d / beta - nu * tr_sw - nu * dx.dot(&w.dot(&dx)) - d * ln_2pi);
+            ln_p_mu += half * (d * (prior.beta0.ln() - ln_2pi) + e_ln_det[j] - d * prior.beta0 / beta
+                - prior.beta0 * nu * dm.dot(&w.dot(&dm)))
+                + half * (prior.nu0 - d - T::one()) * e_ln_det[j]
+                - half * nu * (&w0_inv * w).sum();
+            let entropy = -ln_b(ln_det_w[j], nu) - half * (nu - d - T::one()) * e_ln_det[j] + half * nu * d;
+            ln_q_mu += half * e_ln_det[j] + half * d * (beta.ln() - ln_2pi) - half * d - entropy;
+        }
+        ln_p_mu += k * ln_b(ln_det_w0, prior.nu0);
+        let ln_p_z = r.dot(&e_ln_pi).sum();
+        let ln_p_pi = ln_c(&Array1::from_elem(self.n_components(), prior.alpha0))
+            + (prior.alpha0 - T::one()) * e_ln_pi.sum();
+        let ln_q_z = r.fold(T::zero(), |acc, &v| if v > T::zero() { acc + v * v.ln() } else { acc });
+        let ln_q_pi = Zip::from(&self.alpha).and(&e_ln_pi).fold(T::zero(), |acc, &a, &e| acc + (a - T::one()) * e)
+            + ln_c(&self.alpha);
+        Ok(ln_p_x + ln_p_z + ln_p_pi + ln_p_mu - ln_q_z - ln_q_pi - ln_q_mu)
+    }
+
+    /// Posterior mean of the weights, alpha_k / sum(alpha).
+    pub fn weights(&self) -> Array1<T> {
+        let total = self.alpha.sum();
+        self.alpha.map(|&a| a / total)
+    }
+
+    /// Posterior probability of each component for each row of `x`, n_samples x n_components.
+    pub fn predict_proba(&self, x: &Array2<T>) -> Array2<T> {
+        self.responsibilities(x).0
+    }
+
+    /// Most probable component for each row of `x`.
+    pub fn predict(&self, x: &Array2<T>) -> Array1<usize> {
+        self.responsibilities(x)
+            .1
+            .genrows()
+            .into_iter()
+            .map(|row| {
+                let mut best = 0;
+                for (j, &v) in row.iter().enumerate() {
+                    if v > row[best] {
+                        best = j;
+                    }
+                }
+                best
+            })
+            .collect()
+    }
+
+    /// Log of the posterior predictive density at each row of `x`, a mixture of Student's t (PRML 10.81).
+    pub fn score_samples(&self, x: &Array2<T>) -> Array1<T> {
+        let d = T::from(self.dim()).unwrap();
+        let half = T::from(0.5).unwrap();
+        let pi = T::from(std::f64::consts::PI).unwrap();
+        let ln_det_w = &self.ln_det_w;
+        let ln_weights = self.weights().map(|&w| w.ln());
+        let mut lj = self.quad_form(x);
+        for (j, mut col) in lj.gencolumns_mut().into_iter().enumerate() {
+            let dof = self.nu[j] + T::one() - d;
+            let scale = dof * self.beta[j] / (T::one() + self.beta[j]);
+            let c = ln_weights[j] + ln_gamma(half * (dof + d)) - ln_gamma(half * dof)
+                + half * (d * scale.ln() + ln_det_w[j])
+                - half * d * (dof * pi).ln();
+            col.map_inplace(|q| *q = c - half * (dof + d) * (scale * *q / dof).ln_1p());
+        }
+        lj.genrows().into_iter().map(ln_sum_exp).collect()
+    }
+
+    /// Gaussian mixture at the posterior means of the weights, means and covariances E[Lambda_k]^-1.
+    pub fn to_gaussian_mixture(&self) -> Result<GaussianMixture<T>, ParamError> {
+        let components = (0..self.n_components())
+            .map(|j| {
+                let nu = self.nu[j];
+                let w_inv = self.w[j].invc().map_err(|_| ParamError::NotPositiveDefinite("w"))?;
+                MultivariateGaussian::new(self.m.row(j).to_owned(), w_inv.map(|&v| v / nu))
+            })
+            .collect::<Result<_, _>>()?;
+        GaussianMixture::new(self.weights(), components)
+    }
+}
+
+/// Dirichlet distribution over the probability simplex with concentration `alpha`.
+pub struct Dirichlet<T: Float> {
+    alpha: Array1<T>,
+}
+
+impl<T: FloatConst> Dirichlet<T> {
+    pub fn new(alpha: Array1<T>) -> Result<Self, ParamError> {
+        for &a in alpha.iter() {
+            check_positive("alpha", a)?;
+        }
+        Ok(Self { alpha })
+    }
+
+    pub fn alpha(&self) -> &Array1<T> {
+        &self.alpha
+    }
+
+    pub fn cov(&self) -> Array2<T> {
+        let a0 = self.alpha.sum();
+        let k = self.alpha.len();
+        let denom = a0 * a0 * (a0 + T::one());
+        let mut c = Array2::zeros((k, k));
+        for i in 0..k {
+            for j in 0..k {
+                let ai = self.alpha[i];
+                c[[i, j]] = if i == j {
+                    ai * (a0 - ai) / denom
+                } else {
+                    -ai * self.alpha[j] / denom
+                };
+            }
+        }
+        c
+    }
+}
+
+impl<T: FloatConst> MultivariateDistribution<T> for Dirichlet<T> {
+    type Value = Array1<T>;
+
+    fn mean(&self) -> Array1<T> {
+        let a0 = self.alpha.sum();
+        self.alpha.map(|&a| a / a0)
+    }
+
+    fn var(&self) -> Array1<T> {
+        let a0 = self.alpha.sum();
+        self.alpha.map(|&a| a * (a0 - a) / (a0 * a0 * (a0 + T::one())))
+    }
+
+    fn mode(&self) -> Option<Array1<T>> {
+        if self.alpha.iter().any(|&a| a <= T::one()) {
+            return None;
+        }
+        let k = T::from(self.alpha.len()).unwrap();
+        let a0 = self.alpha.sum();
+        Some(self.alpha.map(|&a| (a - T::one()) / (a0 - k)))
+    }
+
+    fn ln_pdf(&self, x: &Array1<T>) -> T {
+        assert_eq!(x.len(), self.alpha.len(), "x must have the same length as alpha");
+        if check_simplex("x", x).is_err() {
+            return T::neg_infinity();
+        }
+        let a0 = self.alpha.sum();
+        self.alpha.iter().zip(x.iter()).fold(ln_gamma(a0), |acc, (&a, &xi)| {
+            acc - ln_gamma(a) + xlny(a - T::one(), xi)
+        })
+    }
+
+    fn sample(&self, rng: &mut Rng) -> Array1<T> {
+        let g = self.alpha.map(|&a| rng.gamma(a));
+        let total = g.sum();
+        g.map(|&e| e / total)
+    }
+}
+
+/// ln |x| and the lower Cholesky factor of a positive definite matrix, or `None` if it is not positive definite.
+fn ln_det_cholesky<T: Float>(x: &Array2<T>) -> Option<(T, Array2<T>)> {
+    let l = x.cholesky(UPLO::Lower).ok()?;
+    let ln_det = l.diag().iter().map(|&e| e.ln()).sum::<T>();
+    Some((ln_det + ln_det, l))
+}
+
+fn check_wishart<T: Float>(nu: T, scale: &Array2<T>) -> Result<(), ParamError> {
+    let d = scale.shape()[0];
+    if scale.shape()[1] != d {
+        return Err(ParamError::ShapeMismatch);
+    }
+    check_finite("nu", nu)?;
+    if nu > T::from(d).unwrap() - T::one() {
+        Ok(())
+    } else {
+        Err(ParamError::OutOfRange("nu"))
+    }
+}
+
+/// Wishart distribution over positive definite matrices with `nu` degrees of freedom and scale matrix `w`.
+pub struct Wishart<T: Float> {
+    nu: T,
+    w: Array2<T>,
+    /// Lower Cholesky factor of `w`
+    l: Array2<T>,
+    w_inv: Array2<T>,
+    ln_det_w: T,
+}
+
+impl<T: FloatConst> Wishart<T> {
+    /// `nu` must exceed dim - 1.
+    pub fn new(nu: T, w: Array2<T>) -> Result<Self, ParamError> {
+        check_wishart(nu, &w)?;
+        match ln_det_cholesky(&w) {
+            Some((ln_det_w, l)) => {
+                let w_inv = w.invc().unwrap();
+                Ok(Self { nu, w, l, w_inv, ln_det_w })
+            }
+            None => Err(ParamError::NotPositiveDefinite("w")),
+        }
+    }
+
+    pub fn nu(&self) -> T {
+        self.nu
+    }
+
+    pub fn w(&self) -> &Array2<T> {
+        &self.w
+    }
+
+    pub fn dim(&self) -> usize {
+        self.w.shape()[0]
+    }
+}
+
+impl<T: FloatConst> MultivariateDistribution<T> for Wishart<T> {
+    type Value = Array2<T>;
+
+    fn mean(&self) -> Array2<T> {
+        let nu = self.nu;
+        self.w.map(|&e| e * nu)
+    }
+
+    fn var(&self) -> Array2<T> {
+        let d = self.dim();
+        let mut v = Array2::zeros((d, d));
+        for i in 0..d {
+            for j in 0..d {
+                let wij = self.w[[i, j]];
+                v[[i, j]] = self.nu * (wij * wij + self.w[[i, i]] * self.w[[j, j]]);
+            }
+        }
+        v
+    }
+
+    fn mode(&self) -> Option<Array2<T>> {
+        let c = self.nu - T::from(self.dim() + 1).unwrap();
+        if c < T::zero() {
+            None
+        } else {
+            Some(self.w.map(|&e| e * c))
+        }
+    }
+
+    fn ln_pdf(&self, x: &Array2<T>) -> T {
+        let (ln_det_x, _) = match ln_det_cholesky(x) {
+            Some(r) => r,
+            None => return T::neg_infinity(),
+        };
+        let half = T::from(0.5).unwrap();
+        let d = T::from(self.dim()).unwrap();
+        let tr = (&self.w_inv * x).sum();
+        let ln_2 = T::from(std::f64::consts::LN_2).unwrap();
+        half * (self.nu - d - T::one()) * ln_det_x - half * tr - half * self.nu * d * ln_2
+            - half * self.nu * self.ln_det_w - ln_gamma_multi(half * self.nu, self.dim())
+    }
+
+    /// Bartlett decomposition.
+    fn sample(&self, rng: &mut Rng) -> Array2<T> {
+        let d = self.dim();
+        let half = T::from(0.5).unwrap();
+        let mut a = Array2::zeros((d, d));
+        for i in 0..d {
+            let k = self.nu - T::from(i).unwrap();
+            a[[i, i]] = (rng.gamma(half * k) * T::from(2).unwrap()).sqrt();
+            for j in 0..i {
+                a[[i, j]] = rng.normal();
+            }
+        }
+        let la = self.l.dot(&a);
+        la.dot(&la.t())
+    }
+}
+
+/// Inverse-Wishart distribution with `nu` degrees of freedom and scale matrix `psi`.
+pub struct InverseWishart<T: Float> {
+    nu: T,
+    psi: Array2<T>,
+    ln_det_psi: T,
+    /// Wishart distribution of the inverse
+    wishart: Wishart<T>,
+}
+
+impl<T: FloatConst> InverseWishart<T> {
+    /// `nu` must exceed dim - 1.
+    pub fn new(nu: T, psi: Array2<T>) -> Result<Self, ParamError> {
+        check_wishart(nu, &psi)?;
+        match ln_det_cholesky(&psi) {
+            Some((ln_det_psi, _)) => {
+                let wishart = Wishart::new(nu, psi.invc().unwrap())?;
+                Ok(Self { nu, psi, ln_det_psi, wishart })
+            }
+            None => Err(ParamError::NotPositiveDefinite("psi")),
+        }
+    }
+
+    pub fn nu(&self) -> T {
+        self.nu
+    }
+
+    pub fn psi(&self) -> &Array2<T> {
+        &self.psi
+    }
+
+    pub fn dim(&self) -> usize {
+        self.psi.shape()[0]
+    }
+}
+
+impl<T: FloatConst> MultivariateDistribution<T> for InverseWishart<T> {
+    type Value = Array2<T>;
+
+    /// NaN unless nu > dim + 1
+    fn mean(&self) -> Array2<T> {
+        let c = self.nu - T::from(self.dim() + 1).unwrap();
+        if c > T::zero() {
+            self.psi.map(|&e| e / c)
+        } else {
+            self.psi.map(|_| T::nan())
+        }
+    }
+
+    /// NaN unless nu > dim + 3
+    fn var(&self) -> Array2<T> {
+        let d = self.dim();
+        let one = T::one();
+        let np = self.nu - T::from(d).unwrap();
+        let denom = np * (np - one) * (np - one) * (np - T::from(3).unwrap());
+        let mut v = Array2::zeros((d, d));
+        for i in 0..d {
+            for j in 0..d {
+                let pij = self.psi[[i, j]];
+                v[[i, j]] = if np > T::from(3).unwrap() {
+                    ((np + one) * pij * pij + (np - one) * self.psi[[i, i]] * self.psi[[j, j]]) / denom
+                } else {
+                    T::nan()
+                };
+            }
+        }
+        v
+    }
+
+    fn mode(&self) -> Option<Array2<T>> {
+        let c = self.nu + T::from(self.dim() + 1).unwrap();
+        Some(self.psi.map(|&e| e / c))
+    }
+
+    fn ln_pdf(&self, x: &Array2<T>) -> T {
+        let (ln_det_x, _) = match ln_det_cholesky(x) {
+            Some(r) => r,
+            None => return T::neg_infinity(),
+        };
+        let half = T::from(0.5).unwrap();
+        let d = T::from(self.dim()).unwrap();
+        let tr = (&self.psi * &x.invc().unwrap()).sum();
+        let ln_2 = T::from(std::f64::consts::LN_2).unwrap();
+        half * self.nu * self.ln_det_psi - half * self.nu * d * ln_2 - ln_gamma_multi(half * self.nu, self.dim())
+            - half * (self.nu + d + T::one()) * ln_det_x - half * tr
+    }
+
+    fn sample(&self, rng: &mut Rng) -> Array2<T> {
+        self.wishart.sample(rng).invc().unwrap()
+    }
+}
+
+/// Continuous distribution `dist` restricted to [`lo`, `hi`] and renormalized.
+///
+/// When the interval lies in the upper tail of `dist`, probabilities are measured with the survival function
+/// instead of the CDF, so that a mass far below the rounding level of 1 - cdf(lo) is still resolved.
+pub struct Truncated<T: Float, D: Distribution<T>> {
+    dist: D,
+    lo: T,
+    hi: T,
+    /// Whether `p_lo` and `p_hi` are survival probabilities rather than CDF values
+    upper: bool,
+    p_lo: T,
+    p_hi: T,
+    /// Probability mass of `dist` inside [lo, hi]
+    z: T,
+}
+
+impl<T: Float, D: Distribution<T>> Truncated<T, D> {
+    /// `lo` and `hi` may be infinite, but [lo, hi] must carry positive mass under `dist`.
+    pub fn new(dist: D, lo: T, hi: T) -> Result<Self, ParamError> {
+        if lo.is_nan() || hi.is_nan() || lo >= hi {
+            return Err(ParamError::EmptyRange);
+        }
+        let (zero, one) = (T::zero(), T::one());
+        let cdf_lo = if lo == T::neg_infinity() { zero } else { dist.cdf(lo) };
+        let upper = cdf_lo > T::from(0.5).unwrap();
+        let (p_lo, p_hi) = if upper {
+            (dist.sf(lo), if hi == T::infinity() { zero } else { dist.sf(hi) })
+        } else {
+            (cdf_lo, if hi == T::infinity() { one } else { dist.cdf(hi) })
+        };
+        let z = (p_hi - p_lo).abs();
+        if z > zero {
+            Ok(Self { dist, lo, hi, upper, p_lo, p_hi, z })
+        } else {
+            Err(ParamError::OutOfRange("lo, hi"))
+        }
+    }
+
+    pub fn dist(&self) -> &D {
+        &self.dist
+    }
+
+    pub fn lo(&self) -> T {
+        self.lo
+    }
+
+    pub fn hi(&self) -> T {
+        self.hi
+    }
+
+    fn clamp(&self, x: T) -> T {
+        x.max(self.lo).min(self.hi)
+    }
+
+    /// The point with mass `d` of `dist` between it and `lo`, or between it and `hi` if `from_hi`.
+    fn at_mass(&self, d: T, from_hi: bool) -> T {
+        let x = match (self.upper, from_hi) {
+            (false, false) => self.dist.quantile(self.p_lo + d),
+            (false, true) => self.dist.quantile(self.p_hi - d),
+            (true, false) => self.dist.isf(self.p_lo - d),
+            (true, true) => self.dist.isf(self.p_hi + d),
+        };
+        self.clamp(x)
+    }
+
+    /// E[g(X)], computed by tanh-sinh quadrature of g(quantile(u)) over u in (0, 1).
+    pub fn expect<F: Fn(T) -> T>(&self, g: F) -> T {
+        let half = T::from(0.5).unwrap();
+        let half_pi = T::from(std::f64::consts::FRAC_PI_2).unwrap();
+        let h = T::from(1.0 / 32.0).unwrap();
+        let n = 100;
+        (-n..=n).map(|k| {
+            let t = h * T::from(k).unwrap();
+            let s = half_pi * t.sinh();
+            let w = half * half_pi * t.cosh() / (s.cosh() * s.cosh());
+            // Measure from whichever end of (0, 1) the node is nearer, so the offset keeps its precision.
+            let x = if s < T::zero() {
+                self.at_mass(self.z / (T::one() + (-(s + s)).exp()), false)
+            } else {
+                self.at_mass(self.z / (T::one() + (s + s).exp()), true)
+            };
+            let gx = g(x);
+            if w > T::zero() && gx.is_finite() {
+                w * gx
+            } else {
+                T::zero()
+            }
+        }).fold(T::zero(), |acc, e| acc + e) * h
+    }
+
+    /// E[(X - mean)^k]
+    fn central_moment(&self, k: i32) -> T {
+        let m = self.mean();
+        self.expect(|x| (x - m).powi(k))
+    }
+}
+
+impl<T: Float, D: Distribution<T>> Distribution<T> for Truncated<T, D> {
+    fn mean(&self) -> T {
+        self.expect(|x| x)
+    }
+
+    fn var(&self) -> T {
+        self.central_moment(2)
+    }
+
+    /// The mode of `dist` moved into [lo, hi], which assumes `dist` is unimodal.
+    fn mode(&self) -> Option<T> {
+        self.dist.mode().map(|m| self.clamp(m))
+    }
+
+    fn entropy(&self) -> T {
+        -self.expect(|x| self.ln_pdf(x))
+    }
+
+    fn skewness(&self) -> T {
+        self.central_moment(3) / self.var().powf(T::from(1.5).unwrap())
+    }
+
+    fn kurtosis(&self) -> T {
+        let v = self.var();
+        self.central_moment(4) / (v * v) - T::from(3).unwrap()
+    }
+
+    fn pdf(&self, x: T) -> T {
+        if x < self.lo || x > self.hi {
+            T::zero()
+        } else {
+            self.dist.pdf(x) / self.z
+        }
+    }
+
+    fn ln_pdf(&self, x: T) -> T {
+        if x < self.lo || x > self.hi {
+            T::neg_infinity()
+        } else {
+            self.dist.ln_pdf(x) - self.z.ln()
+        }
+    }
+
+    fn cdf(&self, x: T) -> T {
+        if x <= self.lo {
+            T::zero()
+        } else if x >= self.hi {
+            T::one()
+        } else if self.upper {
+            (self.p_lo - self.dist.sf(x)) / self.z
+        } else {
+            (self.dist.cdf(x) - self.p_lo) / self.z
+        }
+    }
+
+    fn sf(&self, x: T) -> T {
+        if x <= self.lo {
+            T::one()
+        } else if x >= self.hi {
+            T::zero()
+        } else if self.upper {
+            (self.dist.sf(x) - self.p_hi) / self.z
+        } else {
+            (self.p_hi - self.dist.cdf(x)) / self.z
+        }
+    }
+
+    fn quantile(&self, p: T) -> T {
+        self.at_mass(p * self.z, false)
+    }
+
+    fn isf(&self, p: T) -> T {
+        self.at_mass(p * self.z, true)
+    }
+
+    /// Rejection sampling when most of the mass is kept, inverse CDF otherwise.
+    fn sample(&self, rng: &mut Rng) -> T {
+        if self.z >= T::from(0.25).unwrap() {
+            loop {
+                let x = self.dist.sample(rng);
+                if x >= self.lo && x <= self.hi {
+                    return x;
+                }
+            }
+        }
+        self.quantile(rng.uniform())
+    }
+}
+
+/// Affine transform `loc + scale * X` of a continuous distribution.
+pub struct LocScale<T: Float, D: Distribution<T>> {
+    dist: D,
+    loc: T,
+    scale: T,
+}
+
+impl<T: Float, D: Distribution<T>> LocScale<T, D> {
+    pub fn new(dist: D, loc: T, scale: T) -> Result<Self, ParamError> {
+        check_finite("loc", loc)?;
+        check_positive("scale", scale)?;
+        Ok(Self { dist, loc, scale })
+    }
+
+    pub fn dist(&self) -> &D {
+        &self.dist
+    }
+
+    pub fn loc(&self) -> T {
+        self.loc
+    }
+
+    pub fn scale(&self) -> T {
+        self.scale
+    }
+
+    fn standardize(&self, x: T) -> T {
+        (x - self.loc) / self.scale
+    }
+}
+
+impl<T: Float, D: Distribution<T>> Distribution<T> for LocScale<T, D> {
+    fn mean(&self) -> T {
+        self.loc + self.scale * self.dist.mean()
+    }
+
+    fn var(&self) -> T {
+        self.scale * self.scale * self.dist.var()
+    }
+
+    fn mode(&self) -> Option<T> {
+        self.dist.mode().map(|m| self.loc + self.scale * m)
+    }
+
+    fn entropy(&self) -> T {
+        self.dist.entropy() + self.scale.ln()
+    }
+
+    fn skewness(&self) -> T {
+        self.dist.skewness()
+    }
+
+    fn kurtosis(&self) -> T {
+        self.dist.kurtosis()
+    }
+
+    fn pdf(&self, x: T) -> T {
+        self.dist.pdf(self.standardize(x)) / self.scale
+    }
+
+    fn ln_pdf(&self, x: T) -> T {
+        self.dist.ln_pdf(self.standardize(x)) - self.scale.ln()
+    }
+
+    fn cdf(&self, x: T) -> T {
+        self.dist.cdf(self.standardize(x))
+    }
+
+    fn sf(&self, x: T) -> T {
+        self.dist.sf(self.standardize(x))
+    }
+
+    fn quantile(&self, p: T) -> T {
+        self.loc + self.scale * self.dist.quantile(p)
+    }
+
+    fn isf(&self, p: T) -> T {
+        self.loc + self.scale * self.dist.isf(p)
+    }
+
+    fn sample(&self, rng: &mut Rng) -> T {
+        self.loc + self.scale * self.dist.sample(rng)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::assert_close;
+
+    #[test]
+    fn bernoulli_pdf_cdf() {
+        let d = Bernoulli::new(0.3).unwrap();
+        assert_close(d.pdf(1.0), 0.3, 1e-15);
+        assert_close(d.pdf(0.0), 0.7, 1e-15);
+        assert_eq!(d.pdf(0.5), 0.0);
+        assert_close(d.cdf(0.5), 0.7, 1e-15);
+        assert_eq!(d.cdf(-1.0), 0.0);
+        assert_eq!(d.cdf(1.0), 1.0);
+    }
+
+    #[test]
+    fn beta_pdf_cdf() {
+        let cases = [
+            (2.0, 3.0, 0.3, 1.764, 0.3483),
+            (0.5, 0.5, 0.1, 1.0610329539459688, 0.20483276469913346),
+            (5.0, 1.0, 0.9, 3.2805, 0.5904900000000001),
+        ];
+        for &(a, b, x, pdf, cdf) in cases.iter() {
+            let d = Beta::new(a, b).unwrap();
+            assert_close(d.pdf(x), pdf, 1e-13);
+            assert_close(d.ln_pdf(x), pdf.ln(), 1e-13);
+            assert_close(d.cdf(x), cdf, 1e-13);
+        }
+    }
+
+    #[test]
+    fn beta_pdf_at_boundary() {
+        assert_close(Beta::new(1.0, 1.0).unwrap().pdf(0.0), 1.0, 1e-15);
+        assert_close(Beta::new(1.0, 1.0).unwrap().pdf(1.0), 1.0, 1e-15);
+        assert_close(Beta::new(1.0, 3.0).unwrap().pdf(0.0), 3.0, 1e-14);
+        assert_close(Beta::new(3.0, 1.0).unwrap().pdf(1.0), 3.0, 1e-14);
+        assert_eq!(Beta::new(2.0, 3.0).unwrap().pdf(0.0), 0.0);
+        assert_eq!(Beta::new(0.5, 3.0).unwrap().pdf(0.0), f64::INFINITY);
+        assert_eq!(Beta::new(2.0, 3.0).unwrap().pdf(1.5), 0.0);
+    }
+
+    #[test]
+    fn gaussian_pdf_cdf() {
+        let d = Gaussian::new(0.5, 2.0).unwrap();
+        assert_close(d.pdf(1.5), 0.2196956447338612, 1e-14);
+        assert_close(d.cdf(-1.0), 0.14442218317324246, 1e-14);
+        assert_close(Gaussian::new(1.0, 0.25).unwrap().ln_pdf(-3.0), -32.22579135264473, 1e-15);
+        assert_close(Gaussian::new(0.0, 1.0).unwrap().cdf(-10.0), 7.619853024160525e-24, 1e-13);
+        let standard = Gaussian::new(0.0, 1.0).unwrap();
+        assert_eq!((standard.cdf(f64::NEG_INFINITY), standard.cdf(f64::INFINITY)), (0.0, 1.0));
+        assert_eq!((standard.sf(f64::NEG_INFINITY), standard.sf(f64::INFINITY)), (1.0, 0.0));
+    }
+
+    #[test]
+    fn quantile_round_trip() {
+        let beta = Beta::new(2.5, 0.7).unwrap();
+        let gaussian = Gaussian::new(-1.0, 4.0).unwrap();
+        for &p in [1e-10, 0.01, 0.3, 0.5, 0.77, 0.999].iter() {
+            assert_close(beta.cdf(beta.quantile(p)), p, 1e-10);
+            assert_close(gaussian.cdf(gaussian.quantile(p)), p, 1e-13);
+        }
+        assert_close(gaussian.quantile(0.975), -1.0 + 2.0 * 1.959963984540054, 1e-15);
+        assert_eq!(gaussian.quantile(0.5), -1.0);
+        assert_eq!(beta.quantile(0.0), 0.0);
+        assert_eq!(beta.quantile(1.0), 1.0);
+    }
+
+    /// Sample mean and variance of `n` draws.
+    fn sample_moments<D: Distribution<f64>>(d: &D, n: usize, seed: u64) -> (f64, f64) {
+        let x = d.sample_n(&mut Rng::new(seed), n);
+        (x.mean().unwrap(), x.var_axis(Axis(0), 0.0).into_scalar())
+    }
+
+    #[test]
+    fn sample_moments_match() {
+        let (m, v) = sample_moments(&Bernoulli::new(0.3).unwrap(), 100_000, 1);
+        assert!((m - 0.3).abs() < 0.01 && (v - 0.21).abs() < 0.01);
+        let (m, v) = sample_moments(&Beta::new(2.0, 5.0).unwrap(), 100_000, 2);
+        assert!((m - 2.0 / 7.0).abs() < 0.005 && (v - 10.0 / 392.0).abs() < 0.001);
+        let (m, v) = sample_moments(&Gaussian::new(-2.0, 9.0).unwrap(), 100_000, 3);
+        assert!((m + 2.0).abs() < 0.05 && (v - 9.0).abs() < 0.15);
+    }
+
+    #[test]
+    fn fit_mle() {
+        let x = arr1(&[1.2, 0.4, 2.2, 3.1, -0.5]);
+        let fit = Gaussian::fit(&x).unwrap();
+        assert_close(fit.dist.mean(), 1.28, 1e-15);
+        assert_close(fit.dist.var(), 1.6216, 1e-14);
+        assert_close(fit.ln_likelihood, -8.303225956412497, 1e-14);
+        let fit = Bernoulli::fit(&arr1(&[1.0, 0.0, 1.0, 1.0])).unwrap();
+        assert_close(fit.dist.mean(), 0.75, 1e-15);
+        assert_eq!(Bernoulli::fit(&arr1(&[0.3, 0.6])).err(), Some(ParamError::OutOfRange("x")));
+        let empty = Array1::<f64>::zeros(0);
+        assert_eq!(Bernoulli::fit(&empty).err(), Some(ParamError::EmptyData));
+        assert_eq!(Beta::fit(&empty).err(), Some(ParamError::EmptyData));
+        // The Beta MLE recovers the parameters of a large sample and is a stationary point of the likelihood.
+        let x = Beta::new(2.0, 5.0).unwrap().sample_n(&mut Rng::new(4), 50_000);
+        let fit = Beta::fit(&x).unwrap();
+        assert!(fit.converged);
+        let (a, b) = (fit.dist.a(), fit.dist.b());
+        assert!((a - 2.0).abs() < 0.05 && (b - 5.0).abs() < 0.1);
+        for &(da, db) in [(1e-3, 0.0), (-1e-3, 0.0), (0.0, 1e-3), (0.0, -1e-3)].iter() {
+            assert!(Beta::new(a + da, b + db).unwrap().ln_likelihood(&x) < fit.ln_likelihood);
+        }
+    }
+
+    #[test]
+    fn conjugate_posteriors() {
+        let x = arr1(&[1.2, 0.4, 2.2, 3.1, -0.5]);
+        let prior = Beta::new(2.0, 3.0).unwrap();
+        let post = prior.posterior(&arr1(&[1.0, 0.0, 1.0, 1.0])).unwrap();
+        assert_eq!((post.a(), post.b()), (5.0, 4.0));
+        assert_close(post.posterior_predictive().mean(), 5.0 / 9.0, 1e-15);
+        assert_eq!(prior.posterior(&arr1(&[1.0, -3.0])).err(), Some(ParamError::OutOfRange("x")));
+
+        let prior = Gaussian::new(0.0, 2.0).unwrap();
+        let post = prior.posterior(1.5, &x).unwrap();
+        assert_close(post.mean(), 1.1130434782608696, 1e-14);
+        assert_close(post.var(), 0.2608695652173913, 1e-14);
+        assert_close(post.posterior_predictive(1.5).unwrap().var(), 0.2608695652173913 + 1.5, 1e-14);
+        for &sigma2 in [-4.0, -2.0, 0.0, f64::NAN].iter() {
+            assert!(prior.posterior(sigma2, &x).is_err() && prior.posterior_predictive(sigma2).is_err());
+        }
+
+        let prior = NormalGamma::new(0.0, 1.0, 1.0, 1.0).unwrap();
+        let post = prior.posterior(&x);
+        assert_close(post.mu(), 1.0666666666666667, 1e-15);
+        assert_eq!((post.lambda(), post.a()), (6.0, 3.5));
+        assert_close(post.b(), 5.736666666666667, 1e-14);
+        let pred = post.posterior_predictive();
+        assert_eq!(pred.nu(), 7.0);
+        assert_close(pred.sigma2(), 1.9122222222222225, 1e-14);
+        // Updating one observation at a time gives the same posterior as a single batch update.
+        let seq = x.iter().fold(prior, |p, &xi| p.posterior(&arr1(&[xi])));
+        assert_close(seq.mu(), post.mu(), 1e-14);
+        assert_close(seq.b(), post.b(), 1e-14);
+    }
+
+    fn mvn() -> MultivariateGaussian<f64> {
+        let sigma = arr2(&[[2.0, 0.5, 0.3], [0.5, 1.0, 0.2], [0.3, 0.2, 1.5]]);
+        MultivariateGaussian::new(arr1(&[1.0, -1.0, 0.5]), sigma).unwrap()
+    }
+
+    #[test]
+    fn multivariate_gaussian() {
+        let d = mvn();
+        let x = arr1(&[0.3, 0.2, 1.0]);
+        assert_close(d.ln_pdf(&x), -4.478886395028041, 1e-14);
+        assert_close(d.mahalanobis(&x), 1.5880392902180795, 1e-14);
+        let c = d.conditional(&[2], &arr1(&[1.0])).unwrap();
+        assert_close(c.mean()[0], 1.1, 1e-14);
+        assert_close(c.mean()[1], -0.9333333333333333, 1e-14);
+        for (&a, &b) in c.cov().iter().zip([1.94, 0.46, 0.46, 0.9733333333333334].iter()) {
+            assert_close(a, b, 1e-14);
+        }
+        let m = d.marginal(&[2, 0]);
+        assert_eq!(m.mean(), arr1(&[0.5, 1.0]));
+        assert_eq!(m.cov(), &arr2(&[[1.5, 0.3], [0.3, 2.0]]));
+        assert!(MultivariateGaussian::new(arr1(&[0.0, 0.0]), arr2(&[[1.0, 2.0], [2.0, 1.0]])).is_err());
+        assert!(MultivariateGaussian::new(arr1(&[0.0]), arr2(&[[1.0, 0.0], [0.0, 1.0]])).is_err());
+    }
+
+    #[test]
+    fn multivariate_gaussian_sample_moments() {
+        let d = mvn();
+        let x = d.sample_n(&mut Rng::new(5), 100_000);
+        let mean = x.mean_axis(Axis(0)).unwrap();
+        let xc = &x - &mean;
+        let cov = xc.t().dot(&xc).map(|&v| v / 100_000.0);
+        assert!((&mean - &d.mean()).iter().all(|v| v.abs() < 0.02));
+        assert!((&cov - d.cov()).iter().all(|v| v.abs() < 0.03));
+    }
+
+    #[test]
+    fn discrete_pmf_cdf() {
+        let binomial = Binomial::new(10, 0.3).unwrap();
+        assert_close(binomial.pmf(3), 0.266827932, 1e-14);
+        assert_close(binomial.cdf(4.5), 0.8497316674, 1e-14);
+        let poisson = Poisson::new(3.5).unwrap();
+        assert_close(poisson.pmf(2), 0.18495897346170082, 1e-14);
+        assert_close(poisson.cdf(5.0), 0.8576135530957784, 1e-14);
+        let geometric = Geometric::new(0.25).unwrap();
+        assert_close(geometric.pmf(3), 0.140625, 1e-15);
+        assert_close(geometric.cdf(3.0), 0.578125, 1e-15);
+        let categorical = Categorical::new(arr1(&[0.2, 0.5, 0.3])).unwrap();
+        assert_close(categorical.cdf(1.0), 0.7, 1e-15);
+        let multinomial = Multinomial::new(5, arr1(&[0.2, 0.3, 0.5])).unwrap();
+        assert_close(multinomial.pmf(&arr1(&[1, 1, 3])), 0.15, 1e-14);
+        assert_eq!(multinomial.pmf(&arr1(&[1, 1, 2])), 0.0);
+    }
+
+    #[test]
+    fn discrete_quantile_at_cdf_values() {
+        let geometric = Geometric::new(0.25).unwrap();
+        let binomial = Binomial::new(10, 0.3).unwrap();
+        let poisson = Poisson::new(3.5).unwrap();
+        let categorical = Categorical::new(arr1(&[0.2, 0.5, 0.3])).unwrap();
+        for k in 1..30 {
+            let k = k as f64;
+            assert_eq!(geometric.quantile(geometric.cdf(k)), k);
+            assert_eq!(geometric.quantile(geometric.cdf(k) + 1e-9), k + 1.0);
+        }
+        for k in 0..10 {
+            let k = k as f64;
+            assert_eq!(binomial.quantile(binomial.cdf(k)), k);
+            assert_eq!(poisson.quantile(poisson.cdf(k)), k);
+        }
+        for k in 0..3 {
+            assert_eq!(categorical.quantile(categorical.cdf(k as f64)), k as f64);
+        }
+        assert_eq!(geometric.quantile(0.0), 1.0);
+        assert_eq!(Geometric::new(1.0).unwrap().quantile(1.0), 1.0);
+    }
+
+    #[test]
+    fn discrete_mode() {
+        assert_eq!(Categorical::new(arr1(&[0.2, 0.5, 0.3])).unwrap().mode(), Some(1.0));
+        assert_eq!(Categorical::new(arr1(&[0.4, 0.2, 0.4])).unwrap().mode(), None);
+        let multinomial = Multinomial::new(5, arr1(&[0.2, 0.3, 0.5])).unwrap();
+        assert_eq!(multinomial.mode(), Some(arr1(&[1.0, 1.0, 3.0])));
+        // [2, 1, 1], [1, 2, 1] and [1, 1, 2] are equally likely.
+        assert_eq!(Multinomial::new(4, arr1(&[1.0 / 3.0, 1.0 / 3.0, 1.0 / 3.0])).unwrap().mode(), None);
+        // (n + 1) p is an integer, so [3, 1] and [2, 2] are equally likely.
+        assert_eq!(Multinomial::new(4, arr1(&[0.6, 0.4])).unwrap().mode(), None);
+        assert_eq!(Multinomial::new(3, arr1(&[0.6, 0.4])).unwrap().mode(), Some(arr1(&[2.0, 1.0])));
+    }
+
+    #[test]
+    fn discrete_fit() {
+        let fit = Poisson::fit(&arr1(&[2.0, 0.0, 3.0, 5.0])).unwrap();
+        assert_close(fit.dist.mean(), 2.5, 1e-15);
+        let fit = Binomial::fit(&arr1(&[2.0, 5.0, 4.0]), 10).unwrap();
+        assert_close(fit.dist.p(), 11.0 / 30.0, 1e-15);
+        let fit = Geometric::fit(&arr1(&[1.0, 3.0, 2.0, 2.0])).unwrap();
+        assert_close(fit.dist.p(), 0.5, 1e-15);
+        let fit = Categorical::fit(&arr1(&[0.0, 2.0, 2.0, 1.0]), 4).unwrap();
+        assert_eq!(fit.dist.p(), &arr1(&[0.25, 0.25, 0.5, 0.0]));
+        let x = arr2(&[[1, 2, 0], [0, 1, 2]]);
+        let fit = Multinomial::<f64>::fit(&x).unwrap();
+        assert_eq!(fit.dist.n(), 3);
+        assert_close(fit.dist.p()[1], 0.5, 1e-15);
+        let expected = fit.dist.ln_pmf(&arr1(&[1, 2, 0])) + fit.dist.ln_pmf(&arr1(&[0, 1, 2]));
+        assert_close(fit.ln_likelihood, expected, 1e-14);
+
+        let empty = Array1::<f64>::zeros(0);
+        assert_eq!(Bernoulli::fit(&empty).err(), Some(ParamError::EmptyData));
+        assert_eq!(Gaussian::fit(&empty).err(), Some(ParamError::EmptyData));
+        assert_eq!(Poisson::fit(&empty).err(), Some(ParamError::EmptyData));
+        assert_eq!(Binomial::fit(&empty, 3).err(), Some(ParamError::EmptyData));
+        assert_eq!(Geometric::fit(&empty).err(), Some(ParamError::EmptyData));
+        assert_eq!(Categorical::fit(&empty, 3).err(), Some(ParamError::EmptyData));
+        let no_rows = Array2::<usize>::zeros((0, 3));
+        assert_eq!(Multinomial::<f64>::fit(&no_rows).err(), Some(ParamError::EmptyData));
+        assert_eq!(Categorical::fit(&arr1(&[0.0, 3.0]), 3).err(), Some(ParamError::OutOfRange("x")));
+        assert_eq!(Categorical::fit(&arr1(&[0.5]), 3).err(), Some(ParamError::OutOfRange("x")));
+        assert_eq!(Multinomial::<f64>::fit(&arr2(&[[1, 2], [0, 2]])).err(), Some(ParamError::OutOfRange("x")));
+        assert_eq!(Poisson::fit(&arr1(&[0.0, 0.0])).err(), Some(ParamError::NotPositive("lambda")));
+        let bad = [arr1(&[-1.0, 2.5]), arr1(&[1.0, 2.5]), arr1(&[1.0, f64::INFINITY])];
+        for x in bad.iter() {
+            assert_eq!(Binomial::fit(x, 3).err(), Some(ParamError::OutOfRange("x")));
+            assert_eq!(Poisson::fit(x).err(), Some(ParamError::OutOfRange("x")));
+            assert_eq!(Geometric::fit(x).err(), Some(ParamError::OutOfRange("x")));
+        }
+        assert_eq!(Binomial::fit(&arr1(&[1.0, 4.0]), 3).err(), Some(ParamError::OutOfRange("x")));
+        assert_eq!(Geometric::fit(&arr1(&[0.0, 2.0])).err(), Some(ParamError::OutOfRange("x")));
+    }
+
+    #[test]
+    fn continuous_pdf_cdf_quantile() {
+        // (distribution, x, pdf(x), cdf(x), p, quantile(p))
+        let cases: Vec<(Box<dyn Distribution<f64>>, [f64; 5])> = vec![
+            (Box::new(Gamma::new(2.5, 1.5).unwrap()), [1.2, 0.45043668078371296, 0.39168670791853133, 0.9, 3.078785633260373]),
+            (Box::new(Exponential::new(2.0).unwrap()), [0.7, 0.493193927883213, 0.7534030360583935, 0.3, 0.1783374719693662]),
+            (Box::new(StudentT::new(4.0, 1.0, 2.25).unwrap()), [2.0, 0.19210836785522906, 0.7292651303622075, 0.95, 4.197770179489975]),
+            (Box::new(ChiSquared::new(3.0).unwrap()), [2.5, 0.18072239266818127, 0.5247089166569794, 0.99, 11.344866730144371]),
+            (Box::new(Laplace::new(1.0, 2.0).unwrap()), [-1.0, 0.09196986029286058, 0.18393972058572117, 0.8, 2.83258146374831]),
+            (Box::new(Uniform::new(-1.0, 3.0).unwrap()), [0.5, 0.25, 0.375, 0.25, 0.0]),
+            (Box::new(Cauchy::new(0.5, 2.0).unwrap()), [-3.0, 0.039176601376466544, 0.1652493405385679, 0.9, 6.655367074350507]),
+        ];
+        for (d, [x, pdf, cdf, p, q]) in cases.iter() {
+            assert_close(d.pdf(*x), *pdf, 1e-13);
+            assert_close(d.cdf(*x), *cdf, 1e-13);
+            assert_close(d.quantile(*p), *q, 1e-12);
+            // Absolute slack for quantiles that sit next to a finite support bound, like the uniform's.
+            for &p in [1e-8, 0.05, 0.5, 0.93].iter() {
+                assert!((d.cdf(d.quantile(p)) - p).abs() <= 1e-11 * p + 1e-16);
+            }
+        }
+        assert_close(StudentT::new(4.0, 1.0, 2.25).unwrap().cdf(-3.0), 0.028, 1e-13);
+        assert_eq!(Gamma::new(2.5, 1.5).unwrap().cdf(-1.0), 0.0);
+        assert_eq!(Exponential::new(2.0).unwrap().pdf(-1.0), 0.0);
+        assert_eq!(Uniform::new(-1.0, 3.0).unwrap().pdf(3.5), 0.0);
+    }
+
+    #[test]
+    fn continuous_sample_moments() {
+        let (m, v) = sample_moments(&Gamma::new(2.5, 1.5).unwrap(), 100_000, 5);
+        assert!((m - 2.5 / 1.5).abs() < 0.01 && (v - 2.5 / 2.25).abs() < 0.02);
+        let (m, v) = sample_moments(&Exponential::new(2.0).unwrap(), 100_000, 6);
+        assert!((m - 0.5).abs() < 0.005 && (v - 0.25).abs() < 0.01);
+        let (m, v) = sample_moments(&StudentT::new(6.0, 1.0, 2.25).unwrap(), 100_000, 7);
+        assert!((m - 1.0).abs() < 0.02 && (v - 3.375).abs() < 0.15);
+        let (m, v) = sample_moments(&ChiSquared::new(3.0).unwrap(), 100_000, 8);
+        assert!((m - 3.0).abs() < 0.03 && (v - 6.0).abs() < 0.15);
+        let (m, v) = sample_moments(&Laplace::new(1.0, 2.0).unwrap(), 100_000, 9);
+        assert!((m - 1.0).abs() < 0.03 && (v - 8.0).abs() < 0.2);
+        let (m, v) = sample_moments(&Uniform::new(-1.0, 3.0).unwrap(), 100_000, 10);
+        assert!((m - 1.0).abs() < 0.01 && (v - 4.0 / 3.0).abs() < 0.02);
+        // The Cauchy has no moments; check the median and quartiles instead.
+        let cauchy = Cauchy::new(0.5, 2.0).unwrap();
+        let mut x = cauchy.sample_n(&mut Rng::new(11), 100_000).to_vec();
+        x.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert!((x[50_000] - 0.5).abs() < 0.03 && (x[75_000] - x[25_000] - 4.0).abs() < 0.06);
+    }
+
+    #[test]
+    fn dirichlet() {
+        let d = Dirichlet::new(arr1(&[2.0, 3.5, 0.7])).unwrap();
+        assert_close(d.ln_pdf(&arr1(&[0.2, 0.5, 0.3])), 0.6893439164223705, 1e-13);
+        assert_eq!(d.ln_pdf(&arr1(&[-0.2, 0.9, 0.3])), f64::NEG_INFINITY);
+        assert_eq!(d.ln_pdf(&arr1(&[0.2, 0.5, 0.4])), f64::NEG_INFINITY);
+        assert_eq!(d.ln_pdf(&arr1(&[0.2, 0.5, 1.3])), f64::NEG_INFINITY);
+        assert_eq!(d.mode(), None);
+        let mode = Dirichlet::new(arr1(&[2.0, 3.0, 5.0])).unwrap().mode().unwrap();
+        assert!((mode - arr1(&[1.0 / 7.0, 2.0 / 7.0, 4.0 / 7.0])).iter().all(|v| v.abs() < 1e-15));
+        // With two components the first coordinate is Beta distributed.
+        let d2 = Dirichlet::new(arr1(&[2.5, 0.7])).unwrap();
+        let beta = Beta::new(2.5, 0.7).unwrap();
+        assert_close(d2.ln_pdf(&arr1(&[0.3, 0.7])), beta.ln_pdf(0.3), 1e-13);
+
+        let mut rng = Rng::new(12);
+        let n = 50_000;
+        let mut x = Array2::<f64>::zeros((n, 3));
+        for mut row in x.genrows_mut() {
+            row.assign(&d.sample(&mut rng));
+        }
+        assert!(x.genrows().into_iter().all(|r| (r.sum() - 1.0).abs() < 1e-12));
+        let mean = x.mean_axis(Axis(0)).unwrap();
+        let xc = &x - &mean;
+        let cov = xc.t().dot(&xc).map(|&v| v / n as f64);
+        assert!((&mean - &d.mean()).iter().all(|v| v.abs() < 0.003));
+        assert!((&cov - &d.cov()).iter().all(|v| v.abs() < 0.001));
+        assert!((cov.diag().to_owned() - d.var()).iter().all(|v| v.abs() < 0.001));
+    }
+
+    #[test]
+    fn wishart() {
+        let x = arr2(&[[3.0, -0.5], [-0.5, 2.0]]);
+        let w = Wishart::new(4.5, arr2(&[[2.0, 0.3], [0.3, 1.0]])).unwrap();
+        assert_close(w.ln_pdf(&x), -5.787075017239038, 1e-13);
+        assert_eq!(w.ln_pdf(&arr2(&[[1.0, 2.0], [2.0, 1.0]])), f64::NEG_INFINITY);
+        assert!((w.mode().unwrap() - arr2(&[[3.0, 0.45], [0.45, 1.5]])).iter().all(|v| v.abs() < 1e-15));
+        assert!(Wishart::new(0.9, Array2::<f64>::eye(2)).is_err());
+        assert!(Wishart::new(3.0, arr2(&[[1.0, 2.0], [2.0, 1.0]])).is_err());
+
+        let iw = InverseWishart::new(5.0, arr2(&[[1.5, 0.2], [0.2, 0.8]])).unwrap();
+        assert_close(iw.ln_pdf(&x), -11.43548964437783, 1e-13);
+        // X ~ IW(nu, psi) iff X^-1 ~ W(nu, psi^-1), with Jacobian |X|^-(d + 1).
+        let x_inv = x.invc().unwrap();
+        let w_inv = Wishart::new(5.0, iw.psi().invc().unwrap()).unwrap();
+        assert_close(iw.ln_pdf(&x), w_inv.ln_pdf(&x_inv) - 3.0 * x.det().unwrap().ln(), 1e-13);
+    }
+
+    #[test]
+    fn wishart_sample_moments() {
+        let n = 50_000;
+        let mut rng = Rng::new(13);
+        let w = Wishart::new(4.5, arr2(&[[2.0, 0.3], [0.3, 1.0]])).unwrap();
+        let samples: Vec<Array2<f64>> = (0..n).map(|_| w.sample(&mut rng)).collect();
+        let mean = samples.iter().fold(Array2::<f64>::zeros((2, 2)), |acc, s| acc + s).map(|&v| v / n as f64);
+        let var = samples.iter().fold(Array2::<f64>::zeros((2, 2)), |acc, s| acc + (s - &mean).map(|&v| v * v));
+        let var = var.map(|&v| v / n as f64);
+        assert!((&mean - &w.mean()).iter().all(|v| v.abs() < 0.1));
+        assert!((&var - &w.var()).iter().zip(w.var().iter()).all(|(d, v)| d.abs() < 0.05 * v));
+
+        let iw = InverseWishart::new(8.0, arr2(&[[1.5, 0.2], [0.2, 0.8]])).unwrap();
+        let samples: Vec<Array2<f64>> = (0..n).map(|_| iw.sample(&mut rng)).collect();
+        let mean = samples.iter().fold(Array2::<f64>::zeros((2, 2)), |acc, s| acc + s).map(|&v| v / n as f64);
+        assert!((&mean - &iw.mean()).iter().all(|v| v.abs() < 0.005));
+    }
+
+    #[test]
+    fn entropy_values() {
+        assert_close(Gaussian::new(1.0, 2.0).unwrap().entropy(), 1.7655121234846454, 1e-14);
+        assert_close(Beta::new(2.5, 0.7).unwrap().entropy(), -0.5619900676836489, 1e-13);
+        assert_close(Bernoulli::new(0.3).unwrap().entropy(), 0.6108643020548935, 1e-14);
+        assert_close(Gamma::new(2.5, 1.5).unwrap().entropy(), 1.32448280139689, 1e-13);
+        assert_close(StudentT::new(4.0, 1.0, 2.25).unwrap().entropy(), 2.0872251249868308, 1e-13);
+        assert_close(Poisson::new(3.5).unwrap().entropy(), 2.015172522512972, 1e-12);
+        assert_close(Binomial::new(10, 0.3).unwrap().entropy(), 1.779078784090063, 1e-12);
+        assert_eq!(Bernoulli::new(0.0).unwrap().entropy(), 0.0);
+    }
+
+    #[test]
+    fn kl_divergence_values() {
+        let (g1, g2) = (Gaussian::new(1.0, 2.0).unwrap(), Gaussian::new(0.0, 3.0).unwrap());
+        assert_close(g1.kl_divergence(&g2), 0.2027325540540822, 1e-14);
+        let (b1, b2) = (Beta::new(2.0, 3.0).unwrap(), Beta::new(1.5, 4.0).unwrap());
+        assert_close(b1.kl_divergence(&b2), 0.23973658042876045, 1e-13);
+        let (p1, p2) = (Bernoulli::new(0.3).unwrap(), Bernoulli::new(0.6).unwrap());
+        assert_close(p1.kl_divergence(&p2), 0.18378689738681228, 1e-14);
+        assert_eq!(g1.kl_divergence(&g1), 0.0);
+        assert!(b1.kl_divergence(&b1).abs() < 1e-15);
+        assert_eq!(p1.kl_divergence(&p1), 0.0);
+    }
+
+    #[test]
+    fn higher_moments() {
+        let beta = Beta::new(2.0, 5.0).unwrap();
+        assert_close(beta.skewness(), 0.5962847939999439, 1e-14);
+        assert_close(beta.kurtosis(), -0.12, 1e-13);
+        let gamma = Gamma::new(2.5, 1.5).unwrap();
+        assert_close(gamma.skewness(), 2.0 / 2.5f64.sqrt(), 1e-15);
+        assert_close(gamma.kurtosis(), 6.0 / 2.5, 1e-15);
+        assert_close(Exponential::new(2.0).unwrap().skewness(), 2.0, 1e-15);
+        assert_close(Exponential::new(2.0).unwrap().kurtosis(), 6.0, 1e-15);
+        assert_close(Poisson::new(4.0).unwrap().skewness(), 0.5, 1e-15);
+        assert_close(Poisson::new(4.0).unwrap().kurtosis(), 0.25, 1e-15);
+        assert_close(Laplace::new(1.0, 2.0).unwrap().kurtosis(), 3.0, 1e-15);
+        assert_close(Uniform::new(-1.0, 3.0).unwrap().kurtosis(), -1.2, 1e-15);
+        assert_close(StudentT::new(6.0, 1.0, 2.25).unwrap().kurtosis(), 3.0, 1e-15);
+        assert_eq!(Gaussian::new(1.0, 2.0).unwrap().skewness(), 0.0);
+        assert_eq!(Gaussian::new(1.0, 2.0).unwrap().kurtosis(), 0.0);
+
+        // Sample skewness and excess kurtosis of a large Gamma sample.
+        let x = gamma.sample_n(&mut Rng::new(14), 200_000);
+        let m = x.mean().unwrap();
+        let moment = |k: i32| x.map(|&v| (v - m).powi(k)).mean().unwrap();
+        let v = moment(2);
+        assert!((moment(3) / v.powf(1.5) - gamma.skewness()).abs() < 0.05);
+        assert!((moment(4) / (v * v) - 3.0 - gamma.kurtosis()).abs() < 0.2);
+    }
+
+    /// Checks the exponential-family identities of `d` at the points `xs`.
+    fn check_exponential_family<D: ExponentialFamily<f64>>(d: &D, xs: &[f64]) {
+        let eta = d.natural_params();
+        let a = D::ln_partition(&eta);
+        for &x in xs {
+            let expected = eta.dot(&D::sufficient_stats(x)) - a + D::ln_base_measure(x);
+            assert_close(d.ln_pdf(x), expected, 1e-12);
+        }
+        let eta2 = D::from_natural_params(&eta).unwrap().natural_params();
+        assert!((&eta2 - &eta).iter().zip(eta.iter()).all(|(e, v)| e.abs() <= 1e-12 * v.abs().max(1.0)));
+        let m = d.mean_params();
+        let m2 = D::from_mean_params(&m).unwrap().mean_params();
+        assert!((&m2 - &m).iter().zip(m.iter()).all(|(e, v)| e.abs() <= 1e-10 * v.abs().max(1.0)));
+        // The gradient of the log-partition function is the mean of the sufficient statistics.
+        for i in 0..eta.len() {
+            let h = 1e-5 * eta[i].abs().max(1.0);
+            let (mut hi, mut lo) = (eta.clone(), eta.clone());
+            hi[i] += h;
+            lo[i] -= h;
+            let grad = (D::ln_partition(&hi) - D::ln_partition(&lo)) / (2.0 * h);
+            assert!((grad - m[i]).abs() <= 1e-7 * m[i].abs().max(1.0));
+        }
+    }
+
+    #[test]
+    fn exponential_family() {
+        check_exponential_family(&Bernoulli::new(0.3).unwrap(), &[0.0, 1.0]);
+        check_exponential_family(&Beta::new(2.5, 0.7).unwrap(), &[0.1, 0.5, 0.9]);
+        check_exponential_family(&Gaussian::new(1.0, 2.0).unwrap(), &[-2.0, 0.5, 3.0]);
+        check_exponential_family(&Poisson::new(3.5).unwrap(), &[0.0, 2.0, 7.0]);
+        let g = Gaussian::new(1.0, 2.0).unwrap();
+        assert_eq!(g.natural_params(), arr1(&[0.5, -0.25]));
+        assert_eq!(g.mean_params(), arr1(&[1.0, 3.0]));
+        let short = arr1(&[0.5]);
+        assert_eq!(Gaussian::from_natural_params(&short).err(), Some(ParamError::ShapeMismatch));
+        assert_eq!(Beta::from_mean_params(&short).err(), Some(ParamError::ShapeMismatch));
+        assert_eq!(Bernoulli::from_natural_params(&arr1(&[0.5, 1.0])).err(), Some(ParamError::ShapeMismatch));
+        assert_eq!(Poisson::from_mean_params(&arr1(&[-1.0])).err(), Some(ParamError::NotPositive("lambda")));
+        assert_eq!(Gaussian::from_natural_params(&arr1(&[0.5, 0.25])).err(), Some(ParamError::NotPositive("sigma2")));
+        assert_eq!(Gaussian::from_mean_params(&arr1(&[1.0, 0.5])).err(), Some(ParamError::NotPositive("sigma2")));
+        assert!(Beta::from_mean_params(&arr1(&[-0.1, -0.1])).is_err());
+    }
+
+    #[test]
+    fn survival_function() {
+        let gaussian = Gaussian::new(0.0, 1.0).unwrap();
+        assert_close(gaussian.sf(10.0), 7.619853024160525e-24, 1e-13);
+        assert_close(gaussian.isf(1e-20), 9.262340089798407, 1e-14);
+        assert_close(StudentT::new(4.0, 1.0, 2.25).unwrap().sf(1000.0), 1.5248172997823198e-11, 1e-12);
+        assert_close(Cauchy::new(0.5, 2.0).unwrap().sf(1e8), 6.366197755506801e-9, 1e-14);
+        assert_close(Gamma::new(2.5, 1.5).unwrap().sf(40.0), 3.138579772755296e-24, 1e-12);
+        assert_close(Exponential::new(2.0).unwrap().isf(1e-300), 345.3877639491069, 1e-15);
+        let dists: Vec<Box<dyn Distribution<f64>>> = vec![
+            Box::new(gaussian),
+            Box::new(StudentT::new(4.0, 1.0, 2.25).unwrap()),
+            Box::new(Exponential::new(2.0).unwrap()),
+            Box::new(Laplace::new(1.0, 2.0).unwrap()),
+            Box::new(Cauchy::new(0.5, 2.0).unwrap()),
+            Box::new(Gamma::new(2.5, 1.5).unwrap()),
+            Box::new(ChiSquared::new(3.0).unwrap()),
+            Box::new(LocScale::new(Gaussian::new(0.0, 1.0).unwrap(), 2.0, 3.0).unwrap()),
+        ];
+        for d in dists.iter() {
+            for &p in [1e-15, 1e-4, 0.3, 0.7].iter() {
+                assert_close(d.sf(d.isf(p)), p, 1e-10);
+                assert_close(d.sf(d.quantile(p)), 1.0 - p, 1e-12);
+            }
+        }
+    }
+
+    #[test]
+    fn truncated_half_line() {
+        let half = Truncated::new(Gaussian::new(0.0, 1.0).unwrap(), 0.0, f64::INFINITY).unwrap();
+        assert_close(half.mean(), 0.7978845608028654, 1e-12);
+        assert_close(half.var(), 0.3633802276324187, 1e-12);
+        assert_close(half.entropy(), 0.7257913526447274, 1e-12);
+        assert_close(half.pdf(1.0), 2.0 * Gaussian::new(0.0, 1.0).unwrap().pdf(1.0), 1e-15);
+        assert_eq!((half.cdf(-1.0), half.cdf(f64::INFINITY)), (0.0, 1.0));
+        // Near a bound the quantile is only resolved to the rounding of the bound itself.
+        for &p in [1e-6, 0.25, 0.5, 0.9].iter() {
+            assert!((half.cdf(half.quantile(p)) - p).abs() <= 1e-12 * p + 1e-15);
+        }
+        let (m, v) = sample_moments(&half, 100_000, 15);
+        assert!((m - 0.7978845608028654).abs() < 0.01 && (v - 0.3633802276324187).abs() < 0.01);
+
+        let gamma = Truncated::new(Gamma::new(2.5, 1.5).unwrap(), 10.0, f64::INFINITY).unwrap();
+        assert_close(gamma.mean(), 10.730944763863913, 1e-10);
+        assert!(Truncated::new(Gaussian::new(0.0, 1.0).unwrap(), f64::NEG_INFINITY, f64::INFINITY).is_ok());
+    }
+
+    #[test]
+    fn truncated_far_tail() {
+        // N(0, 1) on [8, 1e300] keeps a mass of 6.2e-16, below the resolution of 1 - cdf(8).
+        let tail = Truncated::new(Gaussian::new(0.0, 1.0).unwrap(), 8.0, 1e300).unwrap();
+        assert_close(tail.mean(), 8.121368112236112, 1e-10);
+        assert_close(tail.var(), 0.014324883443340911, 1e-8);
+        assert_close(tail.entropy(), -1.1090261777654264, 1e-9);
+        assert_close(tail.quantile(0.5), 8.084911007391543, 1e-13);
+        for &p in [1e-6, 0.3, 0.9].iter() {
+            assert!((tail.cdf(tail.quantile(p)) - p).abs() <= 1e-10 * p + 1e-14);
+            assert_close(tail.sf(tail.isf(p)), p, 1e-10);
+        }
+        let x = tail.sample_n(&mut Rng::new(16), 10_000);
+        let mut distinct = x.to_vec();
+        distinct.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        distinct.dedup();
+        assert_eq!(distinct.len(), 10_000);
+        assert!(x.iter().all(|&v| v >= 8.0) && (x.mean().unwrap() - 8.121368112236112).abs() < 0.005);
+
+        // The mirror image in the lower tail.
+        let low = Truncated::new(Gaussian::new(0.0, 1.0).unwrap(), f64::NEG_INFINITY, -8.0).unwrap();
+        assert_close(low.mean(), -8.121368112236112, 1e-10);
+        assert_close(low.quantile(0.5), -8.084911007391543, 1e-13);
+
+        assert_eq!(Truncated::new(Gaussian::new(0.0, 1.0).unwrap(), 1.0, 1.0).err(), Some(ParamError::EmptyRange));
+        let far = Truncated::new(Gaussian::new(0.0, 1.0).unwrap(), 40.0, 50.0);
+        assert_eq!(far.err(), Some(ParamError::OutOfRange("lo, hi")));
+    }
+
+    #[test]
+    fn loc_scale() {
+        let d = LocScale::new(StudentT::new(5.0, 0.0, 1.0).unwrap(), 2.0, 3.0).unwrap();
+        let t = StudentT::new(5.0, 2.0, 9.0).unwrap();
+        for &x in [-10.0, 0.5, 2.0, 7.0].iter() {
+            assert_close(d.pdf(x), t.pdf(x), 1e-14);
+            assert_close(d.cdf(x), t.cdf(x), 1e-14);
+            assert_close(d.sf(x), t.sf(x), 1e-14);
+        }
+        assert_close(d.quantile(0.9), t.quantile(0.9), 1e-14);
+        assert_close(d.isf(1e-9), t.isf(1e-9), 1e-13);
+        assert_close(d.mean(), 2.0, 1e-15);
+        assert_close(d.var(), t.var(), 1e-14);
+        assert_close(d.entropy(), t.entropy(), 1e-14);
+        assert!(LocScale::new(Gaussian::new(0.0, 1.0).unwrap(), 0.0, -1.0).is_err());
+    }
+
+    #[test]
+    fn von_mises() {
+        let d = VonMises::new(0.5, 2.0).unwrap();
+        assert_close(d.pdf(1.0), 0.40385253335183774, 1e-14);
+        assert_close(d.cdf(0.0), 0.26695139637082604, 1e-13);
+        assert_close(d.cdf(2.0), 0.9623104591303717, 1e-13);
+        assert_close(d.quantile(0.3), 0.07892380791771013, 1e-12);
+        assert_close(d.var(), 0.302225342035992, 1e-14);
+        assert_close(d.entropy(), 1.2663212919642859, 1e-14);
+        assert_close(d.trig_moment(1), 1.0 - d.var(), 1e-15);
+        assert_close(d.mean(), 0.5, 1e-15);
+        assert_eq!(d.cdf(-4.0), 0.0);
+        assert_eq!(d.cdf(4.0), 1.0);
+        // The series needs more terms as kappa grows.
+        let d = VonMises::new(0.5, 500.0).unwrap();
+        assert_close(d.cdf(0.55), 0.8681391237022178, 1e-12);
+        // The mean direction is reported on [-pi, pi).
+        let d = VonMises::new(0.5 + 4.0 * std::f64::consts::PI, 2.0).unwrap();
+        assert_close(d.mean(), 0.5, 1e-14);
+        assert!(VonMises::new(0.0, 0.0).is_err());
+    }
+
+    #[test]
+    fn wrapped_gaussian() {
+        // Both the sum over wraps (sigma2 < 2 pi) and the Fourier series.
+        let cases = [
+            (0.8, 0.3815105568946389, 0.00373312807734529, 0.28962321881738756, 1.306073964867554, 0.3296799539643607),
+            (8.0, 0.16427131172208104, 0.1536953817896883, 0.49440985130323767, 1.8375415475264363, 0.9816843611112658),
+        ];
+        for &(sigma2, pdf1, pdf3, cdf0, entropy, var) in cases.iter() {
+            let d = WrappedGaussian::new(0.5, sigma2).unwrap();
+            assert_close(d.pdf(1.0), pdf1, 1e-14);
+            assert_close(d.pdf(-3.0), pdf3, 1e-13);
+            assert_close(d.cdf(0.0), cdf0, 1e-14);
+            assert_close(d.entropy(), entropy, 1e-13);
+            assert_close(d.var(), var, 1e-15);
+            for &p in [0.01, 0.3, 0.9].iter() {
+                assert_close(d.cdf(d.quantile(p)), p, 1e-12);
+            }
+        }
+        assert!(WrappedGaussian::new(0.0, 0.0).is_err());
+    }
+
+    #[test]
+    fn circular_fit() {
+        let mut rng = Rng::new(12);
+        let x = VonMises::new(-2.5, 3.0).unwrap().sample_n(&mut rng, 100_000);
+        let (mu, r) = mean_direction(&x);
+        assert!((mu + 2.5).abs() < 0.01 && (r - VonMises::<f64>::a(3.0)).abs() < 0.005);
+        let fit = VonMises::fit(&x).unwrap();
+        assert!(fit.converged);
+        assert_close(VonMises::<f64>::a(fit.dist.kappa()), r, 1e-12);
+        assert!((fit.dist.kappa() - 3.0).abs() < 0.05);
+
+        let x = WrappedGaussian::new(3.0, 1.5).unwrap().sample_n(&mut rng, 100_000);
+        let fit = WrappedGaussian::fit_moments(&x).unwrap();
+        assert!((fit.mu() - 3.0).abs() < 0.02 && (fit.sigma2() - 1.5).abs() < 0.03);
+        assert_close(fit.mean_resultant_length(), mean_direction(&x).1, 1e-12);
+
+        let empty = Array1::<f64>::zeros(0);
+        assert!(VonMises::fit(&empty).is_err() && WrappedGaussian::fit_moments(&empty).is_err());
+    }
+
+    fn mixture() -> GaussianMixture<f64> {
+        let components = vec![
+            MultivariateGaussian::new(arr1(&[0.0, 0.0]), arr2(&[[1.0, 0.5], [0.5, 1.0]])).unwrap(),
+            MultivariateGaussian::new(arr1(&[4.0, 3.0]), arr2(&[[0.5, 0.0], [0.0, 2.0]])).unwrap(),
+        ];
+        GaussianMixture::new(arr1(&[0.6, 0.4]), components).unwrap()
+    }
+
+    #[test]
+    fn gaussian_mixture() {
+        let gm = mixture();
+        let cases = [([1.0, 1.0], -2.871477268573584), ([3.0, 2.0], -3.948876167088662), ([-2.0, 5.0], -28.20485201126449)];
+        for &(x, lp) in cases.iter() {
+            assert_close(gm.ln_pdf(&arr1(&x)), lp, 1e-14);
+        }
+        let m = gm.mean();
+        assert_close(m[0], 1.6, 1e-15);
+        assert_close(m[1], 1.2, 1e-15);
+        let c = gm.cov();
+        for &(i, j, v) in [(0, 0, 4.64), (0, 1, 3.18), (1, 0, 3.18), (1, 1, 3.56)].iter() {
+            assert_close(c[[i, j]], v, 1e-14);
+        }
+        let x = arr2(&[[1.0, 1.0], [4.0, 3.0], [-2.0, 5.0]]);
+        assert_eq!(gm.predict(&x), arr1(&[0, 1, 0]));
+        let p = gm.predict_proba(&x);
+        for row in p.genrows() {
+            assert_close(row.sum(), 1.0, 1e-14);
+        }
+        // The mode is a stationary point higher than both component means.
+        let mode = gm.mode().unwrap();
+        let h = 1e-5;
+        for i in 0..2 {
+            let mut e = Array1::zeros(2);
+            e[i] = h;
+            let g = (gm.ln_pdf(&(&mode + &e)) - gm.ln_pdf(&(&mode - &e))) / (2.0 * h);
+            assert!(g.abs() < 1e-6);
+        }
+        assert!(gm.components().iter().all(|c| gm.ln_pdf(&mode) >= gm.ln_pdf(&c.mean())));
+        assert!(GaussianMixture::new(arr1(&[0.5, 0.6]), mixture().components).is_err());
+    }
+
+    #[test]
+    fn gaussian_mixture_fit() {
+        let (x, _) = mixture().sample_n(&mut Rng::new(13), 4000);
+        // The spherical and tied models are misspecified here, which moves their estimates further.
+        let cases = [
+            (CovarianceType::Full, 11, 0.1),
+            (CovarianceType::Diagonal, 9, 0.1),
+            (CovarianceType::Spherical, 7, 0.2),
+            (CovarianceType::Tied, 8, 0.2),
+        ];
+        for &(covariance_type, p, tol) in cases.iter() {
+            let fit = GaussianMixture::fit(&x, 2, covariance_type, &mut Rng::new(14)).unwrap();
+            assert!(fit.converged);
+            let gm = fit.dist;
+            assert_eq!(gm.n_params(), p);
+            let ll = gm.ln_likelihood(&x);
+            assert_close(gm.bic(&x), -2.0 * ll + p as f64 * 4000f64.ln(), 1e-14);
+            assert_close(gm.aic(&x), -2.0 * ll + 2.0 * p as f64, 1e-14);
+            // The clusters are well separated whatever the covariance structure.
+            let j = if gm.components()[0].mean()[0] < 2.0 { 0 } else { 1 };
+            assert!((gm.weights()[j] - 0.6).abs() < 0.3 * tol);
+            assert!((&gm.components()[j].mean() - &arr1(&[0.0, 0.0])).iter().all(|v| v.abs() < tol));
+            assert!((&gm.components()[1 - j].mean() - &arr1(&[4.0, 3.0])).iter().all(|v| v.abs() < 1.5 * tol));
+        }
+        let full = GaussianMixture::fit(&x, 2, CovarianceType::Full, &mut Rng::new(14)).unwrap().dist;
+        let j = if full.components()[0].mean()[0] < 2.0 { 0 } else { 1 };
+        assert!((full.components()[j].cov() - &arr2(&[[1.0, 0.5], [0.5, 1.0]])).iter().all(|v| v.abs() < 0.1));
+
+        // One component is the Gaussian maximum likelihood estimate plus the ridge.
+        let fit = GaussianMixture::fit(&x, 1, CovarianceType::Full, &mut Rng::new(15)).unwrap();
+        let reg = 1e-6 * crate::stats::var(&x, 0).mean().unwrap();
+        let cov = crate::stats::cov(&x, 0) + Array2::<f64>::eye(2) * reg;
+        let c = &fit.dist.components()[0];
+        for (&a, &b) in c.mean().iter().zip(crate::stats::mean(&x).iter()) {
+            assert_close(a, b, 1e-12);
+        }
+        for (&a, &b) in c.cov().iter().zip(cov.iter()) {
+            assert_close(a, b, 1e-12);
+        }
+        assert_close(fit.ln_likelihood, fit.dist.ln_likelihood(&x), 1e-15);
+    }
+
+    #[test]
+    fn gaussian_mixture_fit_scale() {
+        // The ridge scales with the data, so the fit at a tiny scale is the same fit scaled.
+        let (x, _) = mixture().sample_n(&mut Rng::new(16), 500);
+        let scale = 1e-5;
+        let a = GaussianMixture::fit(&x, 2, CovarianceType::Full, &mut Rng::new(17)).unwrap().dist;
+        let b = GaussianMixture::fit(&x.map(|&v| v * scale), 2, CovarianceType::Full, &mut Rng::new(17)).unwrap().dist;
+        for (ca, cb) in a.components().iter().zip(b.components().iter()) {
+            for (&u, &v) in ca.cov().iter().zip(cb.cov().iter()) {
+                assert!((v / (scale * scale) - u).abs() < 1e-8 * ca.cov()[[0, 0]]);
+            }
+        }
+    }
+
+    #[test]
+    fn gaussian_mixture_fit_errors() {
+        let x = mixture().sample_n(&mut Rng::new(18), 10).0;
+        let mut rng = Rng::new(19);
+        assert_eq!(GaussianMixture::fit(&x, 0, CovarianceType::Full, &mut rng).err(), Some(ParamError::OutOfRange("k")));
+        assert_eq!(GaussianMixture::fit(&x, 11, CovarianceType::Full, &mut rng).err(), Some(ParamError::OutOfRange("k")));
+        let empty = Array2::<f64>::zeros((0, 2));
+        assert_eq!(GaussianMixture::fit(&empty, 1, CovarianceType::Full, &mut rng).err(), Some(ParamError::EmptyData));
+        let constant = Array2::from_elem((10, 2), 3.0);
+        let fit = GaussianMixture::fit(&constant, 2, CovarianceType::Diagonal, &mut rng);
+        assert_eq!(fit.err(), Some(ParamError::NotPositiveDefinite("sigma")));
+    }
+
+    #[test]
+    fn bayesian_mixture_elbo() {
+        let (x, _) = mixture().sample_n(&mut Rng::new(20), 1000);
+        let prior = MixturePrior::from_data(&x).unwrap();
+        // Coordinate ascent never lowers the bound while the number of components stays fixed.
+        let vb = BayesianGaussianMixture::fit(&x, 2, &prior, &mut Rng::new(21)).unwrap();
+        assert!(vb.converged() && vb.n_components() == 2);
+        for pair in vb.elbo().windows(2) {
+            assert!(pair[1] >= pair[0] - 1e-10 * pair[0].abs(), "{} < {}", pair[1], pair[0]);
+        }
+        // Unneeded components empty out and are pruned.
+        let vb = BayesianGaussianMixture::fit(&x, 8, &prior, &mut Rng::new(22)).unwrap();
+        assert_eq!(vb.n_components(), 2);
+        let mut w = vb.weights().to_vec();
+        w.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert!((w[0] - 0.4).abs() < 0.05 && (w[1] - 0.6).abs() < 0.05);
+        // With many samples the predictive density is close to the plug-in mixture.
+        let gm = vb.to_gaussian_mixture().unwrap();
+        let pts = arr2(&[[0.0, 0.0], [4.0, 3.0], [2.0, 1.0]]);
+        for (&a, &b) in vb.score_samples(&pts).iter().zip(gm.score_samples(&pts).iter()) {
+            assert!((a - b).abs() < 0.02);
+        }
+        assert_eq!(vb.predict(&pts), gm.predict(&pts));
+    }
+
+    #[test]
+    fn bayesian_mixture_evidence() {
+        // With one component the variational posterior is exact, so the bound is the log evidence of the
+        // Gaussian-Wishart model.
+        let (x, _) = mixture().sample_n(&mut Rng::new(23), 50);
+        let prior = MixturePrior::from_data(&x).unwrap();
+        let vb = BayesianGaussianMixture::fit(&x, 1, &prior, &mut Rng::new(24)).unwrap();
+        let (n, d) = (50.0, 2);
+        let df = d as f64;
+        let dx = &crate::stats::mean(&x) - prior.m0();
+        let c = prior.beta0() * n / (prior.beta0() + n);
+        let mut s_n = prior.w0().invc().unwrap() + crate::stats::cov(&x, 0) * n;
+        for ((i, j), v) in s_n.indexed_iter_mut() {
+            *v += c * dx[i] * dx[j];
+        }
+        let (nu0, nu_n) = (prior.nu0(), prior.nu0() + n);
+        let evidence = -0.5 * n * df * std::f64::consts::PI.ln() + ln_gamma_multi(0.5 * nu_n, d)
+            - ln_gamma_multi(0.5 * nu0, d)
+            - 0.5 * nu0 * ln_det_cholesky(prior.w0()).unwrap().0
+            - 0.5 * nu_n * ln_det_cholesky(&s_n).unwrap().0
+            + 0.5 * df * (prior.beta0() / (prior.beta0() + n)).ln();
+        for &elbo in vb.elbo().iter() {
+            assert_close(elbo, evidence, 1e-12);
+        }
+    }
+
+    #[test]
+    fn bayesian_mixture_errors() {
+        let x = mixture().sample_n(&mut Rng::new(25), 10).0;
+        let prior = MixturePrior::from_data(&x).unwrap();
+        let mut rng = Rng::new(26);
+        assert_eq!(BayesianGaussianMixture::fit(&x, 0, &prior, &mut rng).err(), Some(ParamError::OutOfRange("k")));
+        assert_eq!(BayesianGaussianMixture::fit(&x, 11, &prior, &mut rng).err(), Some(ParamError::OutOfRange("k")));
+        let wide = MixturePrior::new(1.0, 1.0, Array1::zeros(3), 3.0, Array2::eye(3)).unwrap();
+        assert_eq!(BayesianGaussianMixture::fit(&x, 2, &wide, &mut rng).err(), Some(ParamError::ShapeMismatch));
+        let m0 = Array1::<f64>::zeros(2);
+        let cases = [
+            (0.0, 1.0, m0.clone(), 2.0, Array2::eye(2), ParamError::NotPositive("alpha0")),
+            (1.0, -1.0, m0.clone(), 2.0, Array2::eye(2), ParamError::NotPositive("beta0")),
+            (1.0, 1.0, arr1(&[0.0, f64::NAN]), 2.0, Array2::eye(2), ParamError::NotFinite("m0")),
+            (1.0, 1.0, m0.clone(), 2.0, Array2::eye(3), ParamError::ShapeMismatch),
+            (1.0, 1.0, m0.clone(), 2.0, Array2::zeros((2, 3)), ParamError::ShapeMismatch),
+            (1.0, 1.0, m0.clone(), 1.0, Array2::eye(2), ParamError::OutOfRange("nu0")),
+            (1.0, 1.0, m0.clone(), 2.0, Array2::zeros((2, 2)), ParamError::NotPositiveDefinite("w0")),
+        ];
+        for (alpha0, beta0, m0, nu0, w0, err) in cases.iter().cloned() {
+            assert_eq!(MixturePrior::new(alpha0, beta0, m0, nu0, w0).err(), Some(err));
+        }
+        assert_eq!(MixturePrior::from_data(&x.slice(s![..1, ..]).to_owned()).err(), Some(ParamError::EmptyData));
+        let constant = Array2::from_elem((10, 2), 1.0);
+        assert_eq!(MixturePrior::from_data(&constant).err(), Some(ParamError::NotPositiveDefinite("w0")));
+    }
+
+    #[test]
+    #[should_panic(expected = "same length as alpha")]
+    fn dirichlet_length_mismatch() {
+        Dirichlet::new(arr1(&[2.0, 3.5, 0.7])).unwrap().ln_pdf(&arr1(&[0.2, 0.8]));
+    }
+}
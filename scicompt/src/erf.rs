@@ -0,0 +1,210 @@
+use crate::consts::FloatConst;
+
+/// Error function.
+pub fn erf<T: FloatConst>(x: T) -> T {
+    let one = T::one();
+    if x.abs() < T::from(0.5).unwrap() {
+        return erf_series(x);
+    }
+    if x >= T::zero() {
+        one - erfc_cheb(x)
+    } else {
+        erfc_cheb(-x) - one
+    }
+}
+
+/// Complementary error function, 1 - erf(x).
+pub fn erfc<T: FloatConst>(x: T) -> T {
+    if x >= T::zero() {
+        erfc_cheb(x)
+    } else {
+        T::from(2).unwrap() - erfc_cheb(-x)
+    }
+}
+
+/// Maclaurin series of erf, used for small |x| where 1 - erfc(x) would cancel.
+fn erf_series<T: FloatConst>(x: T) -> T {
+    let x2 = x * x;
+    let mut term = x;
+    let mut sum = x;
+    for n in 1..100 {
+        let nf = T::from(n).unwrap();
+        term = -term * x2 / nf;
+        let add = term / (nf + nf + T::one());
+        sum += add;
+        if add.abs() <= sum.abs() * T::epsilon() {
+            break;
+        }
+    }
+    sum * T::from(std::f64::consts::FRAC_2_SQRT_PI).unwrap()
+}
+
+/// Scaled complementary error function, exp(x^2) * erfc(x), which stays representable for large x.
+pub fn erfcx<T: FloatConst>(x: T) -> T {
+    if x >= T::zero() {
+        erfcx_cheb(x)
+    } else {
+        T::from(2).unwrap() * (x * x).exp() - erfcx_cheb(-x)
+    }
+}
+
+/// erfc for x >= 0.
+fn erfc_cheb<T: FloatConst>(z: T) -> T {
+    erfcx_cheb(z) * exp_neg_sq(z)
+}
+
+/// Chebyshev approximation of exp(z^2) * erfc(z) for z >= 0.
+fn erfcx_cheb<T: FloatConst>(z: T) -> T {
+    let cof = T::ERFC_CHEB;
+    let two = T::from(2).unwrap();
+    let half = T::from(0.5).unwrap();
+    let t = two / (two + z);
+    let ty = T::from(4).unwrap() * t - two;
+    let mut d = T::zero();
+    let mut dd = T::zero();
+    for &c in cof[1..].iter().rev() {
+        let tmp = d;
+        d = ty * d - dd + c;
+        dd = tmp;
+    }
+    t * (half * (cof[0] + ty * d) - dd).exp()
+}
+
+/// exp(-z^2) without the relative error that rounding z^2 would amplify in the tail.
+fn exp_neg_sq<T: FloatConst>(z: T) -> T {
+    // Past the smallest subnormal the result is zero, which also keeps z = inf away from inf - inf below.
+    if z * z > -(T::min_positive_value() * T::epsilon()).ln() {
+        return T::zero();
+    }
+    // zh has few enough significant bits that zh * zh is exact.
+    let sixteen = T::from(16).unwrap();
+    let zh = (z * sixteen).floor() / sixteen;
+    (-zh * zh).exp() * (-(z - zh) * (z + zh)).exp()
+}
+
+/// Inverse error function on (-1, 1).
+pub fn erfinv<T: FloatConst>(y: T) -> T {
+    let one = T::one();
+    if y <= -one {
+        return T::neg_infinity();
+    }
+    if y >= one {
+        return T::infinity();
+    }
+    if y.abs() > T::from(0.5).unwrap() {
+        return erfcinv(one - y);
+    }
+    // Halley iteration on erf(x) - y, starting from the first term of the series.
+    let two_sqrt_pi_inv = T::from(std::f64::consts::FRAC_2_SQRT_PI).unwrap();
+    let mut x = y / two_sqrt_pi_inv;
+    for _ in 0..10 {
+        let err = erf(x) - y;
+        let dx = err / (two_sqrt_pi_inv * (-x * x).exp() + x * err);
+        x -= dx;
+        if dx.abs() <= x.abs() * T::epsilon() {
+            break;
+        }
+    }
+    x
+}
+
+/// Inverse complementary error function on (0, 2).
+pub fn erfcinv<T: FloatConst>(p: T) -> T {
+    let one = T::one();
+    let two = T::from(2).unwrap();
+    if p >= two {
+        return T::neg_infinity();
+    }
+    if p <= T::zero() {
+        return T::infinity();
+    }
+    if (p - one).abs() <= T::from(0.5).unwrap() {
+        // 1 - p is exact here, and erfinv avoids the cancellation in erfc(x) - p near x = 0.
+        return erfinv(one - p);
+    }
+    let pp = if p < one { p } else { two - p };
+    let t = (-two * (pp / two).ln()).sqrt();
+    let c = |v: f64| T::from(v).unwrap();
+    let mut x = -c(std::f64::consts::FRAC_1_SQRT_2) * ((c(2.30753) + t * c(0.27061)) / (one + t * (c(0.99229) + t * c(0.04481))) - t);
+    let two_sqrt_pi_inv = T::from(std::f64::consts::FRAC_2_SQRT_PI).unwrap();
+    for _ in 0..20 {
+        let err = erfc(x) - pp;
+        let dx = err / (two_sqrt_pi_inv * (-x * x).exp() - x * err);
+        x += dx;
+        if dx.abs() <= x.abs() * T::epsilon() {
+            break;
+        }
+    }
+    if p < one {
+        x
+    } else {
+        -x
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::assert_close;
+
+    #[test]
+    fn erf_values() {
+        // (x, erf(x), erfc(x))
+        let cases = [
+            (1e-10, 1.1283791670955126e-10, 0.999999999887162),
+            (0.3, 0.3286267594591274, 0.6713732405408726),
+            (0.5, 0.5204998778130465, 0.4795001221869535),
+            (-0.7, -0.6778011938374184, 1.6778011938374184),
+            (1.5, 0.9661051464753108, 0.033894853524689274),
+            (3.0, 0.9999779095030014, 2.209049699858544e-5),
+            (-4.0, -0.9999999845827421, 1.999999984582742),
+            (6.0, 1.0, 2.1519736712498913e-17),
+            (10.0, 1.0, 2.088487583762545e-45),
+            (26.0, 1.0, 5.663192408856143e-296),
+        ];
+        for &(x, e, ec) in cases.iter() {
+            assert_close(erf(x), e, 1e-15);
+            assert_close(erfc(x), ec, 1e-14);
+        }
+        // Subnormal results keep only a few digits.
+        assert_close(erfc(27.0), 5.23705e-319, 1e-4);
+        assert_eq!(erfc(30.0), 0.0);
+        assert_eq!((erf(f64::INFINITY), erf(f64::NEG_INFINITY)), (1.0, -1.0));
+        assert_eq!((erfc(f64::INFINITY), erfc(f64::NEG_INFINITY)), (0.0, 2.0));
+        assert_eq!((erf(f32::INFINITY), erfc(f32::NEG_INFINITY)), (1.0, 2.0));
+        assert!(erf(f64::NAN).is_nan() && erfc(f64::NAN).is_nan());
+    }
+
+    #[test]
+    fn erfcx_values() {
+        assert_close(erfcx(0.3), 0.7345993345676551, 1e-15);
+        assert_close(erfcx(-0.7), 2.7387021025613167, 1e-15);
+        assert_close(erfcx(1.5), 0.3215854164543175, 1e-15);
+        assert_close(erfcx(-4.0), 17772220.904016286, 1e-15);
+        assert_close(erfcx(10.0), 0.05614099274382259, 1e-15);
+        assert_close(erfcx(27.0), 0.02088160799042094, 1e-15);
+        assert_close(erfcx(100.0), 0.005641613782989433, 1e-15);
+        assert_close(erfcx(1e5), 5.6418958351954685e-6, 1e-15);
+        assert_eq!(erfcx(f64::INFINITY), 0.0);
+        assert_eq!(erfcx(-30.0), f64::INFINITY);
+    }
+
+    #[test]
+    fn erfinv_values() {
+        assert_close(erfinv(0.5), 0.4769362762044699, 1e-15);
+        assert_close(erfinv(-0.9), -1.163087153676674, 1e-15);
+        assert_close(erfinv(0.999), 2.3267537655135246, 1e-14);
+        assert_close(erfinv(1e-5), 8.862269254759594e-6, 1e-15);
+        assert_eq!(erfinv(1.0), f64::INFINITY);
+        assert_eq!(erfinv(-1.0), f64::NEG_INFINITY);
+    }
+
+    #[test]
+    fn erfcinv_values() {
+        assert_close(erfcinv(1e-10), 4.572824967389486, 1e-15);
+        assert_close(erfcinv(1.9), -1.163087153676674, 1e-14);
+        assert_eq!(erfcinv(1.0), 0.0);
+        assert_eq!(erfcinv(0.0), f64::INFINITY);
+        assert_eq!(erfcinv(2.0), f64::NEG_INFINITY);
+    }
+}
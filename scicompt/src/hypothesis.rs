@@ -0,0 +1,650 @@
+use ndarray::*;
+
+use crate::beta::beta_inc;
+use crate::consts::FloatConst;
+use crate::distribution::Distribution;
+use crate::erf::erfc;
+use crate::gamma::{gamma_q, ln_gamma};
+
+/// Alternative hypothesis. `Less` and `Greater` refer to the first sample (or the data against the null value).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Alternative {
+    TwoSided,
+    Less,
+    Greater,
+}
+
+#[derive(Clone, Debug)]
+pub struct TestResult<T: FloatConst> {
+    pub statistic: T,
+    pub p_value: T,
+    /// Degrees of freedom of the reference distribution, for the t and chi-squared tests
+    pub df: Option<T>,
+}
+
+/// P(T >= t) for Student's t with `nu` degrees of freedom.
+fn t_sf<T: FloatConst>(t: T, nu: T) -> T {
+    let half = T::from(0.5).unwrap();
+    let tail = half * beta_inc(half * nu, half, nu / (nu + t * t));
+    if t > T::zero() {
+        tail
+    } else {
+        T::one() - tail
+    }
+}
+
+/// P(Z >= z) for the standard normal.
+fn normal_sf<T: FloatConst>(z: T) -> T {
+    T::from(0.5).unwrap() * erfc(z * T::from(std::f64::consts::FRAC_1_SQRT_2).unwrap())
+}
+
+/// p-value of a statistic whose null distribution is symmetric, from its survival function.
+fn symmetric_p<T: FloatConst, F: Fn(T) -> T>(stat: T, alternative: Alternative, sf: F) -> T {
+    match alternative {
+        Alternative::TwoSided => (sf(stat.abs()) * T::from(2).unwrap()).min(T::one()),
+        Alternative::Less => sf(-stat),
+        Alternative::Greater => sf(stat),
+    }
+}
+
+fn t_result<T: FloatConst>(t: T, df: T, alternative: Alternative) -> TestResult<T> {
+    let p_value = symmetric_p(t, alternative, |s| t_sf(s, df));
+    TestResult { statistic: t, p_value, df: Some(df) }
+}
+
+fn mean_var<T: FloatConst>(x: &Array1<T>) -> (T, T) {
+    (x.mean().expect("samples must not be empty"), x.var_axis(Axis(0), T::one()).into_scalar())
+}
+
+/// One-sample t-test of the mean of `x` against `mu`. Panics if `x` is empty.
+pub fn ttest_1samp<T: FloatConst>(x: &Array1<T>, mu: T, alternative: Alternative) -> TestResult<T> {
+    let n = T::from(x.len()).unwrap();
+    let (m, v) = mean_var(x);
+    t_result((m - mu) / (v / n).sqrt(), n - T::one(), alternative)
+}
+
+/// Two-sample t-test of equal means. With `equal_var` false this is Welch's test, with the Welch-Satterthwaite
+/// degrees of freedom. Panics if either sample is empty.
+pub fn ttest_ind<T: FloatConst>(x: &Array1<T>, y: &Array1<T>, equal_var: bool, alternative: Alternative) -> TestResult<T> {
+    let one = T::one();
+    let (n1, n2) = (T::from(x.len()).unwrap(), T::from(y.len()).unwrap());
+    let (m1, v1) = mean_var(x);
+    let (m2, v2) = mean_var(y);
+    let (se2, df) = if equal_var {
+        let df = n1 + n2 - T::from(2).unwrap();
+        let pooled = ((n1 - one) * v1 + (n2 - one) * v2) / df;
+        (pooled * (one / n1 + one / n2), df)
+    } else {
+        let (a, b) = (v1 / n1, v2 / n2);
+        (a + b, (a + b) * (a + b) / (a * a / (n1 - one) + b * b / (n2 - one)))
+    };
+    t_result((m1 - m2) / se2.sqrt(), df, alternative)
+}
+
+/// Paired t-test, the one-sample test of x - y against zero.
+pub fn ttest_rel<T: FloatConst>(x: &Array1<T>, y: &Array1<T>, alternative: Alternative) -> TestResult<T> {
+    assert_eq!(x.len(), y.len(), "paired samples must have the same length");
+    ttest_1samp(&(x - y), T::zero(), alternative)
+}
+
+/// Pearson's chi-squared goodness-of-fit test of observed counts against `expected` counts (uniform if `None`),
+/// which should have the same total. `ddof` reduces the k - 1 degrees of freedom for fitted parameters; the p-value
+/// is NaN when no degrees of freedom are left.
+pub fn chisquare<T: FloatConst>(observed: &Array1<T>, expected: Option<&Array1<T>>, ddof: usize) -> TestResult<T> {
+    let k = observed.len();
+    let uniform;
+    let expected = match expected {
+        Some(e) => e,
+        None => {
+            uniform = Array1::from_elem(k, observed.sum() / T::from(k).unwrap());
+            &uniform
+        }
+    };
+    let stat = Zip::from(observed).and(expected).fold(T::zero(), |acc, &o, &e| acc + (o - e) * (o - e) / e);
+    let df = T::from(k).unwrap() - T::one() - T::from(ddof).unwrap();
+    let half = T::from(0.5).unwrap();
+    let p_value = if df > T::zero() { gamma_q(half * df, half * stat) } else { T::nan() };
+    TestResult { statistic: stat, p_value, df: Some(df) }
+}
+
+/// Chi-squared test of independence of the rows and columns of a contingency table of counts. `correction`
+/// applies Yates' continuity correction when there is one degree of freedom. Panics if the table is empty.
+pub fn chi2_contingency<T: FloatConst>(table: &Array2<T>, correction: bool) -> TestResult<T> {
+    assert!(table.nrows() > 0 && table.ncols() > 0, "table must have at least one row and one column");
+    let half = T::from(0.5).unwrap();
+    let rows = table.sum_axis(Axis(1));
+    let cols = table.sum_axis(Axis(0));
+    let total = table.sum();
+    let df = T::from((table.nrows() - 1) * (table.ncols() - 1)).unwrap();
+    let yates = correction && df == T::one();
+    let mut stat = T::zero();
+    for ((i, j), &o) in table.indexed_iter() {
+        let e = rows[i] * cols[j] / total;
+        let mut d = (o - e).abs();
+        if yates {
+            d -= d.min(half);
+        }
+        stat += d * d / e;
+    }
+    TestResult { statistic: stat, p_value: gamma_q(half * df, half * stat), df: Some(df) }
+}
+
+/// P(K > lambda) for the limiting Kolmogorov distribution.
+fn kolmogorov_sf<T: FloatConst>(lambda: T) -> T {
+    let one = T::one();
+    if lambda <= T::zero() {
+        return one;
+    }
+    let pi = T::from(std::f64::consts::PI).unwrap();
+    let mut sum = T::zero();
+    if lambda < T::from(1.18).unwrap() {
+        let c = -pi * pi / (T::from(8).unwrap() * lambda * lambda);
+        for k in 1..20 {
+            let m = T::from(2 * k - 1).unwrap();
+            sum += (c * m * m).exp();
+        }
+        one - (pi + pi).sqrt() / lambda * sum
+    } else {
+        let c = T::from(-2).unwrap() * lambda * lambda;
+        for k in 1..20 {
+            let kf = T::from(k).unwrap();
+            let term = (c * kf * kf).exp();
+            sum += if k % 2 == 1 { term } else { -term };
+        }
+        (sum + sum).min(one)
+    }
+}
+
+/// P(D+ >= d) for the one-sided one-sample statistic with `n` observations (Smirnov's exact formula).
+fn smirnov_sf<T: FloatConst>(n: usize, d: T) -> T {
+    let one = T::one();
+    if d <= T::zero() {
+        return one;
+    }
+    if d >= one {
+        return T::zero();
+    }
+    let nf = T::from(n).unwrap();
+    let ln_n_fact = ln_gamma(nf + one);
+    let jmax = (nf * (one - d)).floor().to_usize().unwrap();
+    let mut sum = T::zero();
+    for j in 0..=jmax {
+        let jf = T::from(j).unwrap();
+        let ln_c = ln_n_fact - ln_gamma(jf + one) - ln_gamma(nf - jf + one);
+        let a = one - d - jf / nf;
+        let b = d + jf / nf;
+        let ln_term = ln_c + (nf - jf) * a.ln() + (jf - one) * b.ln();
+        sum += ln_term.exp();
+    }
+    (d * sum).min(one)
+}
+
+/// P(D < d) for the two-sided one-sample statistic with `n` observations (Marsaglia, Tsang and Wang).
+fn kolmogorov_cdf_exact<T: FloatConst>(n: usize, d: T) -> T {
+    let nf = T::from(n).unwrap();
+    let k = (nf * d).floor().to_usize().unwrap() + 1;
+    let m = 2 * k - 1;
+    let h = T::from(k).unwrap() - nf * d;
+    let one = T::one();
+    let mut hm = Array2::<T>::zeros((m, m));
+    for i in 0..m {
+        for j in 0..m {
+            if i + 1 >= j {
+                hm[[i, j]] = one;
+            }
+        }
+    }
+    for i in 0..m {
+        hm[[i, 0]] -= h.powi(i as i32 + 1);
+        hm[[m - 1, i]] -= h.powi((m - i) as i32);
+    }
+    let two_h = h + h - one;
+    if two_h > T::zero() {
+        hm[[m - 1, 0]] += two_h.powi(m as i32);
+    }
+    for i in 0..m {
+        for j in 0..=(i + 1).min(m - 1) {
+            for g in 1..=(i + 1 - j) {
+                hm[[i, j]] /= T::from(g).unwrap();
+            }
+        }
+    }
+    // Q = H^n by repeated squaring, with the scale kept as a power of 10^140 in `eq`.
+    let big = T::from(1e140).unwrap();
+    let mut q = Array2::<T>::eye(m);
+    let mut eq = 0i32;
+    let mut base = hm;
+    let mut eb = 0i32;
+    let mut e = n;
+    while e > 0 {
+        if e % 2 == 1 {
+            q = q.dot(&base);
+            eq += eb;
+            if q[[k - 1, k - 1]] > big {
+                q.mapv_inplace(|v| v / big);
+                eq += 1;
+            }
+        }
+        e /= 2;
+        if e > 0 {
+            base = base.dot(&base);
+            eb += eb;
+            if base[[k - 1, k - 1]] > big {
+                base.mapv_inplace(|v| v / big);
+                eb += 1;
+            }
+        }
+    }
+    let mut s = q[[k - 1, k - 1]];
+    for i in 1..=n {
+        s = s * T::from(i).unwrap() / nf;
+        if s < one / big {
+            s *= big;
+            eq -= 1;
+        }
+    }
+    s * big.powi(eq)
+}
+
+/// P(D >= d) for the two-sided one-sample statistic.
+fn kolmogorov_n_sf<T: FloatConst>(n: usize, d: T) -> T {
+    // Twice the one-sided tail is exact for d >= 1/2 and accurate to O(p^2) below.
+    let p2 = T::from(2).unwrap() * smirnov_sf(n, d);
+    if p2 < T::from(1e-7).unwrap() || d >= T::from(0.5).unwrap() {
+        return p2.min(T::one());
+    }
+    if 2.0 * (n as f64) * d.to_f64().unwrap() < 400.0 {
+        return (T::one() - kolmogorov_cdf_exact(n, d)).max(T::zero());
+    }
+    let sn = T::from(n).unwrap().sqrt();
+    kolmogorov_sf((sn + T::from(0.12).unwrap() + T::from(0.11).unwrap() / sn) * d)
+}
+
+fn sorted<T: FloatConst>(x: &Array1<T>) -> Vec<T> {
+    let mut v = x.to_vec();
+    v.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    v
+}
+
+/// One-sample Kolmogorov-Smirnov test of `x` against `dist`. The statistic is D = sup |F_n - F|, or for the
+/// one-sided alternatives D+ = sup (F_n - F) (`Greater`) and D- = sup (F - F_n) (`Less`). Exact p-values, with
+/// the Kolmogorov limit for large samples. Panics if `x` is empty.
+pub fn ks_1samp<T: FloatConst, D: Distribution<T>>(x: &Array1<T>, dist: &D, alternative: Alternative) -> TestResult<T> {
+    assert!(!x.is_empty(), "samples must not be empty");
+    let v = sorted(x);
+    let n = v.len();
+    let nf = T::from(n).unwrap();
+    let mut d_plus = T::zero();
+    let mut d_minus = T::zero();
+    for (i, &xi) in v.iter().enumerate() {
+        let f = dist.cdf(xi);
+        d_plus = d_plus.max(T::from(i + 1).unwrap() / nf - f);
+        d_minus = d_minus.max(f - T::from(i).unwrap() / nf);
+    }
+    let (statistic, p_value) = match alternative {
+        Alternative::TwoSided => {
+            let d = d_plus.max(d_minus);
+            (d, kolmogorov_n_sf(n, d))
+        }
+        Alternative::Greater => (d_plus, smirnov_sf(n, d_plus)),
+        Alternative::Less => (d_minus, smirnov_sf(n, d_minus)),
+    };
+    TestResult { statistic, p_value, df: None }
+}
+
+/// Two-sample Kolmogorov-Smirnov test. The statistic is sup |F_x - F_y|, or sup (F_x - F_y) for `Greater` and
+/// sup (F_y - F_x) for `Less`. The p-value counts lattice paths exactly when n * m <= 10000 (assuming no ties),
+/// and uses the asymptotic distribution otherwise. Panics if either sample is empty.
+pub fn ks_2samp<T: FloatConst>(x: &Array1<T>, y: &Array1<T>, alternative: Alternative) -> TestResult<T> {
+    assert!(!x.is_empty() && !y.is_empty(), "samples must not be empty");
+    let (vx, vy) = (sorted(x), sorted(y));
+    let (n, m) = (vx.len(), vy.len());
+    let (nf, mf) = (T::from(n).unwrap(), T::from(m).unwrap());
+    let (mut i, mut j) = (0, 0);
+    let mut d_plus = T::zero();
+    let mut d_minus = T::zero();
+    while i < n && j < m {
+        let v = vx[i].min(vy[j]);
+        while i < n && vx[i] <= v {
+            i += 1;
+        }
+        while j < m && vy[j] <= v {
+            j += 1;
+        }
+        let diff = T::from(i).unwrap() / nf - T::from(j).unwrap() / mf;
+        d_plus = d_plus.max(diff);
+        d_minus = d_minus.max(-diff);
+    }
+    let statistic = match alternative {
+        Alternative::TwoSided => d_plus.max(d_minus),
+        Alternative::Greater => d_plus,
+        Alternative::Less => d_minus,
+    };
+    let p_value = if n * m <= 10000 {
+        match alternative {
+            Alternative::TwoSided => ks_2samp_exact(n, m, statistic, true),
+            Alternative::Greater => ks_2samp_exact(n, m, statistic, false),
+            // Swapping the samples turns D- into D+.
+            Alternative::Less => ks_2samp_exact(m, n, statistic, false),
+        }
+    } else {
+        let en = nf * mf / (nf + mf);
+        if alternative == Alternative::TwoSided {
+            let se = en.sqrt();
+            kolmogorov_sf((se + T::from(0.12).unwrap() + T::from(0.11).unwrap() / se) * statistic)
+        } else {
+            (T::from(-2).unwrap() * en * statistic * statistic).exp()
+        }
+    };
+    TestResult { statistic, p_value, df: None }
+}
+
+/// Probability that a random lattice path from (0, 0) to (n, m) reaches i / n - j / m >= d (or |i / n - j / m| >= d).
+fn ks_2samp_exact<T: FloatConst>(n: usize, m: usize, d: T, two_sided: bool) -> T {
+    let one = T::one();
+    // Compare the integer distances |i m - j n| against d n m, allowing for rounding in d.
+    let lim = d.to_f64().unwrap() * (n * m) as f64 - 1e-7;
+    let outside = |i: usize, j: usize| {
+        let gap = (i * m) as f64 - (j * n) as f64;
+        gap >= lim || (two_sided && -gap >= lim)
+    };
+    // Probability of reaching (i, j) while staying inside, with each step weighted by the hypergeometric move.
+    let mut row = vec![T::zero(); m + 1];
+    for i in 0..=n {
+        for j in 0..=m {
+            let p = if i == 0 && j == 0 {
+                one
+            } else if outside(i, j) {
+                T::zero()
+            } else {
+                let left = if j > 0 { row[j - 1] * T::from(m - j + 1).unwrap() } else { T::zero() };
+                let up = if i > 0 { row[j] * T::from(n - i + 1).unwrap() } else { T::zero() };
+                (left + up) / T::from(n + m - i - j + 1).unwrap()
+            };
+            row[j] = p;
+        }
+    }
+    (one - row[m]).max(T::zero()).min(one)
+}
+
+/// Mid-ranks (starting at one) of `v`, and the tie term sum (t^3 - t) over groups of ties.
+fn rank<T: FloatConst>(v: &[T]) -> (Vec<T>, T) {
+    let mut idx: Vec<usize> = (0..v.len()).collect();
+    idx.sort_by(|&a, &b| v[a].partial_cmp(&v[b]).unwrap());
+    let mut ranks = vec![T::zero(); v.len()];
+    let mut ties = T::zero();
+    let mut i = 0;
+    while i < idx.len() {
+        let mut j = i + 1;
+        while j < idx.len() && v[idx[j]] == v[idx[i]] {
+            j += 1;
+        }
+        let r = T::from(i + j + 1).unwrap() / T::from(2).unwrap();
+        for &k in idx[i..j].iter() {
+            ranks[k] = r;
+        }
+        let t = T::from(j - i).unwrap();
+        ties += t * t * t - t;
+        i = j;
+    }
+    (ranks, ties)
+}
+
+/// p-value of an integer-valued statistic `s` from the counts of its null distribution, `counts[k]` for s = k.
+/// The counts are kept in f64 whatever `T` is, since they outgrow the integers f32 represents exactly.
+fn exact_p<T: FloatConst>(s: T, counts: &[f64], alternative: Alternative) -> T {
+    let total: f64 = counts.iter().sum();
+    let k = s.round().to_usize().unwrap();
+    let le = T::from(counts[..=k].iter().sum::<f64>() / total).unwrap();
+    let ge = T::from(counts[k..].iter().sum::<f64>() / total).unwrap();
+    match alternative {
+        Alternative::TwoSided => (T::from(2).unwrap() * le.min(ge)).min(T::one()),
+        Alternative::Less => le,
+        Alternative::Greater => ge,
+    }
+}
+
+/// p-value of a statistic with null mean `mean` and variance `var` by the normal approximation with a continuity
+/// correction of one half.
+fn normal_p<T: FloatConst>(s: T, mean: T, var: T, alternative: Alternative) -> T {
+    let half = T::from(0.5).unwrap();
+    let sd = var.sqrt();
+    match alternative {
+        Alternative::TwoSided => (T::from(2).unwrap() * normal_sf(((s - mean).abs() - half).max(T::zero()) / sd)).min(T::one()),
+        Alternative::Less => normal_sf((mean - s - half) / sd),
+        Alternative::Greater => normal_sf((s - mean - half) / sd),
+    }
+}
+
+/// Mann-Whitney U test. The statistic is U of `x`, the number of pairs with x_i > y_j (ties count one half).
+/// Exact p-value when there are no ties and n * m <= 2500, otherwise the tie-corrected normal approximation.
+pub fn mann_whitney_u<T: FloatConst>(x: &Array1<T>, y: &Array1<T>, alternative: Alternative) -> TestResult<T> {
+    let (n1, n2) = (x.len(), y.len());
+    let all: Vec<T> = x.iter().chain(y.iter()).cloned().collect();
+    let (ranks, ties) = rank(&all);
+    let r1 = ranks[..n1].iter().fold(T::zero(), |a, &r| a + r);
+    let (f1, f2) = (T::from(n1).unwrap(), T::from(n2).unwrap());
+    let u = r1 - f1 * (f1 + T::one()) / T::from(2).unwrap();
+    let p_value = if ties == T::zero() && n1 * n2 <= 2500 {
+        // Coefficients of the Gaussian binomial [n1 + n2, n1]_q = prod_i (1 - q^(n2 + i)) / (1 - q^i).
+        let umax = n1 * n2;
+        let mut c = vec![0.0; umax + 1];
+        c[0] = 1.0;
+        for i in 1..=n1 {
+            for k in (n2 + i..=umax).rev() {
+                c[k] -= c[k - n2 - i];
+            }
+            for k in i..=umax {
+                c[k] += c[k - i];
+            }
+        }
+        exact_p(u, &c, alternative)
+    } else {
+        let nf = f1 + f2;
+        let var = f1 * f2 / T::from(12).unwrap() * (nf + T::one() - ties / (nf * (nf - T::one())));
+        normal_p(u, f1 * f2 / T::from(2).unwrap(), var, alternative)
+    };
+    TestResult { statistic: u, p_value, df: None }
+}
+
+/// Wilcoxon signed-rank test that the differences `d` (for paired data, x - y) are symmetric about zero. Zero
+/// differences are dropped. The statistic is the sum of the ranks of the positive differences. Exact p-value when
+/// there are no ties and at most 50 non-zero differences, otherwise the tie-corrected normal approximation.
+pub fn wilcoxon<T: FloatConst>(d: &Array1<T>, alternative: Alternative) -> TestResult<T> {
+    let nz: Vec<T> = d.iter().cloned().filter(|&v| v != T::zero()).collect();
+    let n = nz.len();
+    let abs: Vec<T> = nz.iter().map(|&v| v.abs()).collect();
+    let (ranks, ties) = rank(&abs);
+    let w = nz.iter().zip(ranks.iter()).fold(T::zero(), |a, (&v, &r)| if v > T::zero() { a + r } else { a });
+    let nf = T::from(n).unwrap();
+    let p_value = if ties == T::zero() && n <= 50 {
+        // Counts of subset sums of {1, ..., n}, the coefficients of prod_i (1 + q^i).
+        let max = n * (n + 1) / 2;
+        let mut c = vec![0.0; max + 1];
+        c[0] = 1.0;
+        for i in 1..=n {
+            for k in (i..=max).rev() {
+                c[k] += c[k - i];
+            }
+        }
+        exact_p(w, &c, alternative)
+    } else {
+        let one = T::one();
+        let mean = nf * (nf + one) / T::from(4).unwrap();
+        let var = nf * (nf + one) * (nf + nf + one) / T::from(24).unwrap() - ties / T::from(48).unwrap();
+        normal_p(w, mean, var, alternative)
+    };
+    TestResult { statistic: w, p_value, df: None }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::distribution::Gaussian;
+    use crate::test_util::assert_close;
+
+    // The exact-test reference values come from enumerating the permutations.
+
+    fn samples() -> (Array1<f64>, Array1<f64>) {
+        let x = arr1(&[1.83, 0.50, 1.62, 2.48, 1.68, 1.88, 1.55, 3.06, 1.30]);
+        let y = arr1(&[0.88, 0.65, 0.60, 2.05, 1.06, 1.29, 3.15]);
+        (x, y)
+    }
+
+    #[test]
+    fn t_tests() {
+        let (x, y) = samples();
+        let cases = [
+            (Alternative::TwoSided, 0.2960322551234084),
+            (Alternative::Less, 0.8519838724382958),
+            (Alternative::Greater, 0.1480161275617042),
+        ];
+        for &(alternative, p) in cases.iter() {
+            let r = ttest_1samp(&x, 1.5, alternative);
+            assert_close(r.statistic, 1.117952110490164, 1e-14);
+            assert_close(r.p_value, p, 1e-13);
+            assert_eq!(r.df, Some(8.0));
+        }
+        let r = ttest_ind(&x, &y, true, Alternative::TwoSided);
+        assert_close(r.statistic, 0.9405141384301031, 1e-14);
+        assert_close(r.p_value, 0.36289969539596634, 1e-13);
+        assert_eq!(r.df, Some(14.0));
+        let r = ttest_ind(&x, &y, false, Alternative::Greater);
+        assert_close(r.statistic, 0.9098846296116021, 1e-14);
+        assert_close(r.p_value, 0.1910882359909048, 1e-13);
+        assert_close(r.df.unwrap(), 11.123345706290616, 1e-14);
+        let r = ttest_rel(&x, &x.map(|&v| v - 1.0), Alternative::TwoSided);
+        assert!(r.statistic.is_infinite() && r.p_value == 0.0);
+    }
+
+    #[test]
+    fn chi_squared_tests() {
+        let observed = arr1(&[16.0, 18.0, 16.0, 14.0, 12.0, 12.0]);
+        let r = chisquare(&observed, None, 0);
+        assert_close(r.statistic, 2.0, 1e-15);
+        assert_close(r.p_value, 0.8491450360846097, 1e-14);
+        let r = chisquare(&observed, None, 1);
+        assert_close(r.p_value, 0.7357588823428847, 1e-14);
+        assert_eq!(r.df, Some(4.0));
+        let r = chisquare(&observed, Some(&arr1(&[16.0, 16.0, 16.0, 16.0, 16.0, 8.0])), 0);
+        assert_close(r.statistic, 3.5, 1e-15);
+        assert_close(r.p_value, 0.623387627749582, 1e-14);
+        // No degrees of freedom left.
+        let r = chisquare(&observed, None, 5);
+        assert!(r.p_value.is_nan() && r.df == Some(0.0));
+        assert!(chisquare(&observed, None, 9).p_value.is_nan());
+
+        let table = arr2(&[[12.0, 5.0], [7.0, 14.0]]);
+        let r = chi2_contingency(&table, true);
+        assert_close(r.statistic, 3.831932773109244, 1e-14);
+        assert_close(r.p_value, 0.050284916060494324, 1e-13);
+        let r = chi2_contingency(&table, false);
+        assert_close(r.statistic, 5.215686274509804, 1e-14);
+        assert_close(r.p_value, 0.02238401069217774, 1e-13);
+        // Yates' correction only applies with one degree of freedom.
+        let r = chi2_contingency(&arr2(&[[10.0, 20.0, 30.0], [15.0, 15.0, 10.0]]), true);
+        assert_close(r.statistic, 8.035714285714286, 1e-14);
+        assert_close(r.p_value, 0.017991476826658484, 1e-13);
+        assert_eq!(r.df, Some(2.0));
+    }
+
+    #[test]
+    fn kolmogorov_smirnov() {
+        let z = arr1(&[-1.2, -0.4, 0.1, 0.3, 0.9, 1.5, 2.2, -0.05]);
+        let g = Gaussian::new(0.0, 1.0).unwrap();
+        let cases = [
+            (Alternative::TwoSided, 0.23006119416162754, 0.7105415135660451),
+            (Alternative::Greater, 0.013903447513498604, 0.984685645357474),
+            (Alternative::Less, 0.23006119416162754, 0.37188698070024245),
+        ];
+        for &(alternative, d, p) in cases.iter() {
+            let r = ks_1samp(&z, &g, alternative);
+            assert_close(r.statistic, d, 1e-14);
+            assert_close(r.p_value, p, 1e-12);
+        }
+        assert_close(kolmogorov_sf(1.0), 0.2699996716773545, 1e-14);
+        assert_close(kolmogorov_sf(0.5), 0.9639452436648751, 1e-14);
+
+        let (x, y) = samples();
+        let cases = [
+            (Alternative::TwoSided, 0.6031746031746031, 0.07867132867132867),
+            (Alternative::Greater, 0.14285714285714285, 0.7789335664335665),
+            (Alternative::Less, 0.6031746031746031, 0.039335664335664336),
+        ];
+        for &(alternative, d, p) in cases.iter() {
+            let r = ks_2samp(&x, &y, alternative);
+            assert_close(r.statistic, d, 1e-14);
+            assert_close(r.p_value, p, 1e-13);
+        }
+    }
+
+    #[test]
+    fn rank_tests() {
+        let (x, y) = samples();
+        let cases = [
+            (Alternative::TwoSided, 0.2991258741258741),
+            (Alternative::Less, 0.8738636363636364),
+            (Alternative::Greater, 0.14956293706293705),
+        ];
+        for &(alternative, p) in cases.iter() {
+            let r = mann_whitney_u(&x, &y, alternative);
+            assert_eq!(r.statistic, 42.0);
+            assert_close(r.p_value, p, 1e-14);
+        }
+        // Ties switch to the normal approximation.
+        let xt = arr1(&[1.0, 2.0, 2.0, 3.0, 5.0, 7.0, 7.0, 8.0]);
+        let yt = arr1(&[2.0, 3.0, 3.0, 4.0, 6.0, 6.0, 9.0, 10.0, 11.0]);
+        let r = mann_whitney_u(&xt, &yt, Alternative::TwoSided);
+        assert_eq!(r.statistic, 25.0);
+        assert_close(r.p_value, 0.30933832403732364, 1e-14);
+
+        let d = arr1(&[0.95, -0.15, 1.02, 0.43, 0.62, 0.59, 0.51, -0.08, 0.10, -0.3]);
+        let cases = [
+            (Alternative::TwoSided, 0.048828125),
+            (Alternative::Less, 0.9814453125),
+            (Alternative::Greater, 0.0244140625),
+        ];
+        for &(alternative, p) in cases.iter() {
+            let r = wilcoxon(&d, alternative);
+            assert_eq!(r.statistic, 47.0);
+            assert_close(r.p_value, p, 1e-15);
+        }
+        let dt = arr1(&[1.0, -2.0, 2.0, 3.0, 3.0, 3.0, -1.0, 4.0, 0.0, 5.0]);
+        let r = wilcoxon(&dt, Alternative::TwoSided);
+        assert_eq!(r.statistic, 40.0);
+        assert_close(r.p_value, 0.04290120601140135, 1e-14);
+        assert_close(wilcoxon(&dt, Alternative::Greater).p_value, 0.021450603005700675, 1e-14);
+    }
+
+    #[test]
+    fn exact_counts_in_single_precision() {
+        // The null counts reach C(60, 30) ~ 1.2e17 and 2^40, far past the integers f32 holds exactly.
+        let x: Array1<f32> = (0..30).map(|i| (2 * i) as f32 + 0.5 * (i % 3) as f32).collect();
+        let y: Array1<f32> = (0..30).map(|i| (2 * i + 1) as f32).collect();
+        let p32 = mann_whitney_u(&x, &y, Alternative::TwoSided).p_value;
+        let p64 = mann_whitney_u(&x.mapv(f64::from), &y.mapv(f64::from), Alternative::TwoSided).p_value;
+        assert_close(f64::from(p32), p64, 1e-6);
+        let d: Array1<f32> = (1..=40).map(|i| if i % 3 == 0 { -(i as f32) } else { i as f32 }).collect();
+        let p32 = wilcoxon(&d, Alternative::Greater).p_value;
+        let p64 = wilcoxon(&d.mapv(f64::from), Alternative::Greater).p_value;
+        assert_close(f64::from(p32), p64, 1e-6);
+    }
+
+    #[test]
+    #[should_panic(expected = "samples must not be empty")]
+    fn ttest_empty() {
+        ttest_ind(&samples().0, &Array1::<f64>::zeros(0), true, Alternative::TwoSided);
+    }
+
+    #[test]
+    #[should_panic(expected = "samples must not be empty")]
+    fn ks_1samp_empty() {
+        ks_1samp(&Array1::<f64>::zeros(0), &Gaussian::new(0.0, 1.0).unwrap(), Alternative::TwoSided);
+    }
+
+    #[test]
+    #[should_panic(expected = "at least one row")]
+    fn chi2_contingency_empty() {
+        chi2_contingency(&Array2::<f64>::zeros((0, 2)), false);
+    }
+}
@@ -0,0 +1,463 @@
+use ndarray::*;
+
+use crate::consts::FloatConst;
+use crate::distribution::ParamError;
+use crate::gamma::ln_gamma;
+use crate::random::Rng;
+use crate::stats;
+
+/// Radial smoothing kernels k(u) of the scaled distance u = |x - x_i| / h, normalized to integrate to one.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DensityKernel {
+    /// exp(-u^2 / 2)
+    Gaussian,
+    /// 1 - u^2 for u < 1
+    Epanechnikov,
+    /// 1 for u < 1
+    Tophat,
+    /// exp(-u)
+    Exponential,
+    /// 1 - u for u < 1
+    Linear,
+}
+
+impl DensityKernel {
+    /// ln k(u), without the normalization.
+    pub fn ln_profile<T: FloatConst>(&self, u: T) -> T {
+        let one = T::one();
+        match self {
+            DensityKernel::Gaussian => -T::from(0.5).unwrap() * u * u,
+            DensityKernel::Exponential => -u,
+            _ if u >= one => T::neg_infinity(),
+            DensityKernel::Epanechnikov => (-u * u).ln_1p(),
+            DensityKernel::Tophat => T::zero(),
+            DensityKernel::Linear => (-u).ln_1p(),
+        }
+    }
+
+    /// ln of the integral of k(|u|) over d-dimensional space.
+    pub fn ln_norm<T: FloatConst>(&self, d: usize) -> T {
+        let df = T::from(d).unwrap();
+        let half_d = T::from(0.5).unwrap() * df;
+        let pi = T::from(std::f64::consts::PI).unwrap();
+        // Volume of the unit ball
+        let ln_v = half_d * pi.ln() - ln_gamma(half_d + T::one());
+        match self {
+            DensityKernel::Gaussian => half_d * (pi + pi).ln(),
+            DensityKernel::Epanechnikov => ln_v + (T::from(2).unwrap() / (df + T::from(2).unwrap())).ln(),
+            DensityKernel::Tophat => ln_v,
+            DensityKernel::Exponential => ln_v + ln_gamma(df + T::one()),
+            DensityKernel::Linear => ln_v - (df + T::one()).ln(),
+        }
+    }
+
+    /// Distance beyond which the kernel is zero, or negligible at double precision.
+    fn tail<T: FloatConst>(&self) -> T {
+        match self {
+            DensityKernel::Gaussian => T::from(8).unwrap(),
+            DensityKernel::Exponential => T::from(32).unwrap(),
+            _ => T::one(),
+        }
+    }
+
+    /// Ratio of the canonical bandwidth (R(K) / mu_2(K)^2)^(1/5) of the kernel to that of the Gaussian, which
+    /// converts a bandwidth chosen for the Gaussian into an equivalent one (Marron and Nolan).
+    fn canonical_ratio<T: FloatConst>(&self) -> T {
+        let gaussian = (0.5 / std::f64::consts::PI.sqrt()).powf(0.2);
+        let delta: f64 = match self {
+            DensityKernel::Gaussian => return T::one(),
+            DensityKernel::Epanechnikov => 15.0,
+            DensityKernel::Tophat => 4.5,
+            DensityKernel::Exponential => 0.0625,
+            DensityKernel::Linear => 24.0,
+        };
+        T::from(delta.powf(0.2) / gaussian).unwrap()
+    }
+
+    /// Offset with density proportional to k(|u|) in `d` dimensions.
+    fn sample<T: FloatConst>(&self, rng: &mut Rng, d: usize) -> Array1<T> {
+        let z: Array1<T> = (0..d).map(|_| rng.normal()).collect();
+        if *self == DensityKernel::Gaussian {
+            return z;
+        }
+        let one = T::one();
+        let df = T::from(d).unwrap();
+        // Radius with density proportional to k(r) r^(d - 1), then a uniform direction.
+        let r = match self {
+            DensityKernel::Exponential => rng.gamma(df),
+            _ => loop {
+                let r = rng.uniform::<T>().powf(one / df);
+                if rng.uniform::<T>().ln() < self.ln_profile(r) {
+                    break r;
+                }
+            },
+        };
+        let norm = z.dot(&z).sqrt();
+        z.map(|&zi| zi * r / norm)
+    }
+}
+
+/// Rule or value for the bandwidth of `KernelDensity::fit`.
+///
+/// The rules are derived for the Gaussian kernel and converted to the other kernels by their canonical bandwidths.
+#[derive(Clone, Copy, Debug)]
+pub enum Bandwidth<T: FloatConst> {
+    Fixed(T),
+    /// n^(-1 / (d + 4)) sigma, with sigma the mean standard deviation of the features
+    Scott,
+    /// (4 / (n (d + 2)))^(1 / (d + 4)) sigma, with the robust spread min(std, IQR / 1.349) for sigma
+    Silverman,
+    /// Maximizes the leave-one-out log-likelihood of the data.
+    CrossValidation,
+}
+
+/// Kernel density estimate with an isotropic bandwidth from `n_samples x n_features` data.
+pub struct KernelDensity<T: FloatConst> {
+    kernel: DensityKernel,
+    bandwidth: T,
+    data: Array2<T>,
+}
+
+impl<T: FloatConst> KernelDensity<T> {
+    /// Fails on empty data, and when the bandwidth is not positive and finite, as the rules give for data without
+    /// spread.
+    pub fn fit(x: &Array2<T>, kernel: DensityKernel, bandwidth: Bandwidth<T>) -> Result<Self, ParamError> {
+        let (n, d) = (x.nrows(), x.ncols());
+        if n == 0 {
+            return Err(ParamError::EmptyData);
+        }
+        let nf = T::from(n).unwrap();
+        let df = T::from(d).unwrap();
+        let four = T::from(4).unwrap();
+        let exponent = T::one() / (df + four);
+        let silverman = || {
+            let std = stats::std(x, 1);
+            let q1 = stats::quantile(x, T::from(0.25).unwrap(), stats::Interpolation::Linear);
+            let q3 = stats::quantile(x, T::from(0.75).unwrap(), stats::Interpolation::Linear);
+            let iqr = q3 - q1;
+            let sigma = Zip::from(&std).and(&iqr).fold(T::zero(), |acc, &s, &r| {
+                let r = r / T::from(1.349).unwrap();
+                acc + if r > T::zero() { s.min(r) } else { s }
+            }) / df;
+            (four / (nf * (df + T::from(2).unwrap()))).powf(exponent) * sigma * kernel.canonical_ratio()
+        };
+        let h = match bandwidth {
+            Bandwidth::Fixed(h) => h,
+            // A single sample has no spread to scale the rules by.
+            _ if n < 2 => T::zero(),
+            Bandwidth::Scott => nf.powf(-exponent) * stats::std(x, 1).mean().unwrap() * kernel.canonical_ratio(),
+            Bandwidth::Silverman => silverman(),
+            Bandwidth::CrossValidation => match silverman() {
+                h0 if h0 > T::zero() => Self::cross_validate(x, kernel, h0),
+                h0 => h0,
+            },
+        };
+        if h.is_nan() || h <= T::zero() {
+            return Err(ParamError::NotPositive("bandwidth"));
+        }
+        if h.is_infinite() {
+            return Err(ParamError::NotFinite("bandwidth"));
+        }
+        Ok(Self { kernel, bandwidth: h, data: x.to_owned() })
+    }
+
+    pub fn kernel(&self) -> DensityKernel {
+        self.kernel
+    }
+
+    pub fn bandwidth(&self) -> T {
+        self.bandwidth
+    }
+
+    pub fn data(&self) -> &Array2<T> {
+        &self.data
+    }
+
+    /// Leave-one-out log-likelihood of `x` with bandwidth `h`.
+    fn loo_ln_likelihood(x: &Array2<T>, kernel: DensityKernel, h: T) -> T {
+        let n = x.nrows();
+        let ln_c = kernel.ln_norm::<T>(x.ncols()) + T::from(x.ncols()).unwrap() * h.ln() + T::from(n - 1).unwrap().ln();
+        let mut total = T::zero();
+        for i in 0..n {
+            let xi = x.row(i);
+            let ln_k: Vec<T> = (0..n)
+                .filter(|&j| j != i)
+                .map(|j| {
+                    let dx = &x.row(j) - &xi;
+                    kernel.ln_profile(dx.dot(&dx).sqrt() / h)
+                })
+                .collect();
+            total += ln_sum_exp(&ln_k) - ln_c;
+        }
+        total
+    }
+
+    /// Grid search over h0 * 10^[-1.5, 0.5], refined by golden section search on ln h.
+    fn cross_validate(x: &Array2<T>, kernel: DensityKernel, h0: T) -> T {
+        let score = |ln_h: T| Self::loo_ln_likelihood(x, kernel, ln_h.exp());
+        let ln10 = T::from(std::f64::consts::LN_10).unwrap();
+        let step = T::from(0.1).unwrap() * ln10;
+        let grid: Vec<T> = (0..21).map(|i| h0.ln() + (T::from(i).unwrap() * T::from(0.1).unwrap() - T::from(1.5).unwrap()) * ln10).collect();
+        let scores: Vec<T> = grid.iter().map(|&g| score(g)).collect();
+        let best = (0..grid.len()).fold(0, |b, i| if scores[i] > scores[b] { i } else { b });
+        let (mut a, mut b) = (grid[best] - step, grid[best] + step);
+        let ratio = T::from(0.5 * (5f64.sqrt() - 1.0)).unwrap();
+        let mut c = b - ratio * (b - a);
+        let mut d = a + ratio * (b - a);
+        let (mut fc, mut fd) = (score(c), score(d));
+        while b - a > T::from(1e-3).unwrap() {
+            if fc > fd {
+                b = d;
+                d = c;
+                fd = fc;
+                c = b - ratio * (b - a);
+                fc = score(c);
+            } else {
+                a = c;
+                c = d;
+                fc = fd;
+                d = a + ratio * (b - a);
+                fd = score(d);
+            }
+        }
+        let ln_h = T::from(0.5).unwrap() * (a + b);
+        if score(ln_h) >= scores[best] {
+            ln_h.exp()
+        } else {
+            grid[best].exp()
+        }
+    }
+
+    fn ln_c(&self) -> T {
+        let d = self.data.ncols();
+        self.kernel.ln_norm::<T>(d) + T::from(d).unwrap() * self.bandwidth.ln() + T::from(self.data.nrows()).unwrap().ln()
+    }
+
+    fn ln_pdf_row(&self, x: ArrayView1<T>, ln_c: T) -> T {
+        let ln_k: Vec<T> = self
+            .data
+            .genrows()
+            .into_iter()
+            .map(|xi| {
+                let dx = &xi - &x;
+                self.kernel.ln_profile(dx.dot(&dx).sqrt() / self.bandwidth)
+            })
+            .collect();
+        ln_sum_exp(&ln_k) - ln_c
+    }
+
+    /// Log density at each row of `x`.
+    pub fn score_samples(&self, x: &Array2<T>) -> Array1<T> {
+        let ln_c = self.ln_c();
+        x.genrows().into_iter().map(|xi| self.ln_pdf_row(xi, ln_c)).collect()
+    }
+
+    /// Total log-likelihood of the rows of `x`.
+    pub fn score(&self, x: &Array2<T>) -> T {
+        self.score_samples(x).sum()
+    }
+
+    pub fn sample(&self, rng: &mut Rng, n: usize) -> Array2<T> {
+        let d = self.data.ncols();
+        let mut out = Array2::zeros((n, d));
+        for mut row in out.genrows_mut() {
+            let i = rng.below(self.data.nrows() as u64) as usize;
+            let offset = self.kernel.sample::<T>(rng, d);
+            Zip::from(&mut row).and(&self.data.row(i)).and(&offset).apply(|o, &xi, &e| {
+                *o = xi + self.bandwidth * e;
+            });
+        }
+        out
+    }
+
+    /// Density of univariate data on `n_grid` equally spaced points covering the data, by linear binning and a
+    /// discrete convolution with the kernel. Costs O(n + n_grid * width) instead of O(n * n_grid).
+    /// Panics unless the data has one feature.
+    pub fn density_grid(&self, n_grid: usize) -> (Array1<T>, Array1<T>) {
+        assert_eq!(self.data.ncols(), 1, "binned density needs univariate data");
+        assert!(n_grid >= 2);
+        let h = self.bandwidth;
+        let x = self.data.column(0);
+        let pad = self.kernel.tail::<T>().min(T::from(4).unwrap()) * h;
+        let lo = x.fold(T::infinity(), |m, &v| m.min(v)) - pad;
+        let hi = x.fold(T::neg_infinity(), |m, &v| m.max(v)) + pad;
+        let delta = (hi - lo) / T::from(n_grid - 1).unwrap();
+        let grid: Array1<T> = (0..n_grid).map(|i| lo + delta * T::from(i).unwrap()).collect();
+        // Linear binning: each sample splits its unit weight between the two nearest grid points.
+        let mut counts = Array1::<T>::zeros(n_grid);
+        for &v in x.iter() {
+            let pos = (v - lo) / delta;
+            let i = pos.floor().to_usize().unwrap().min(n_grid - 2);
+            let frac = pos - T::from(i).unwrap();
+            counts[i] += T::one() - frac;
+            counts[i + 1] += frac;
+        }
+        let width = (self.kernel.tail::<T>() * h / delta).ceil().to_usize().unwrap().min(n_grid - 1);
+        // Kernel weights at offsets -width..=width from the grid point.
+        let w = T::from(width).unwrap();
+        let weights: Vec<T> = (0..=2 * width)
+            .map(|k| self.kernel.ln_profile((T::from(k).unwrap() - w).abs() * delta / h).exp())
+            .collect();
+        let c = (-self.ln_c()).exp();
+        let density = (0..n_grid)
+            .map(|m| {
+                let start = m.saturating_sub(width);
+                let end = (m + width).min(n_grid - 1);
+                let s = (start..=end).fold(T::zero(), |acc, j| {
+                    acc + counts[j] * weights[j + width - m]
+                });
+                s * c
+            })
+            .collect();
+        (grid, density)
+    }
+
+    /// Log density at `x` interpolated linearly from `density_grid(n_grid)`, falling back to the exact sum for points
+    /// outside the grid. Panics unless the data has one feature.
+    pub fn score_samples_binned(&self, x: &Array1<T>, n_grid: usize) -> Array1<T> {
+        let (grid, density) = self.density_grid(n_grid);
+        let lo = grid[0];
+        let delta = grid[1] - grid[0];
+        let ln_c = self.ln_c();
+        x.iter()
+            .map(|&v| {
+                let pos = (v - lo) / delta;
+                if pos >= T::zero() && pos <= T::from(n_grid - 1).unwrap() {
+                    let i = pos.floor().to_usize().unwrap().min(n_grid - 2);
+                    let frac = pos - T::from(i).unwrap();
+                    (density[i] * (T::one() - frac) + density[i + 1] * frac).ln()
+                } else {
+                    self.ln_pdf_row(aview1(&[v]), ln_c)
+                }
+            })
+            .collect()
+    }
+}
+
+fn ln_sum_exp<T: FloatConst>(v: &[T]) -> T {
+    let m = v.iter().fold(T::neg_infinity(), |m, &x| m.max(x));
+    if m == T::neg_infinity() {
+        return m;
+    }
+    m + v.iter().map(|&x| (x - m).exp()).fold(T::zero(), |a, b| a + b).ln()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::assert_close;
+    use std::f64::consts::PI;
+
+    fn data() -> Array2<f64> {
+        arr2(&[[0.0], [1.0], [3.0]])
+    }
+
+    #[test]
+    fn kernel_norm() {
+        let cases = [
+            (DensityKernel::Gaussian, (2.0 * PI).sqrt(), (2.0 * PI).powf(1.5)),
+            (DensityKernel::Epanechnikov, 4.0 / 3.0, 8.0 * PI / 15.0),
+            (DensityKernel::Tophat, 2.0, 4.0 * PI / 3.0),
+            (DensityKernel::Exponential, 2.0, 8.0 * PI),
+            (DensityKernel::Linear, 1.0, PI / 3.0),
+        ];
+        for &(kernel, d1, d3) in cases.iter() {
+            assert_close(kernel.ln_norm::<f64>(1).exp(), d1, 1e-14);
+            assert_close(kernel.ln_norm::<f64>(3).exp(), d3, 1e-14);
+        }
+    }
+
+    // Reference values from mpmath at 30 digits.
+
+    #[test]
+    fn score_samples() {
+        let x = arr2(&[[0.0], [0.8], [2.0], [5.0]]);
+        let kde = KernelDensity::fit(&data(), DensityKernel::Gaussian, Bandwidth::Fixed(0.5)).unwrap();
+        let expected = [-1.197475616855343, -1.1410691241168749, -2.63001785205711, -9.324403641275087];
+        for (&s, &e) in kde.score_samples(&x).iter().zip(expected.iter()) {
+            assert_close(s, e, 1e-14);
+        }
+        assert_close(kde.score(&x), expected.iter().sum(), 1e-14);
+        let kde = KernelDensity::fit(&data(), DensityKernel::Epanechnikov, Bandwidth::Fixed(0.5)).unwrap();
+        let s = kde.score_samples(&x);
+        assert_close(s[0], -std::f64::consts::LN_2, 1e-14);
+        assert_close(s[1], -0.8675005677047231, 1e-14);
+        assert_eq!(s[2], f64::NEG_INFINITY);
+    }
+
+    #[test]
+    fn bandwidth_rules() {
+        // sigma = sqrt(7 / 3), and the IQR of 1.5 gives the smaller spread 1.5 / 1.349.
+        let kde = KernelDensity::fit(&data(), DensityKernel::Gaussian, Bandwidth::Scott).unwrap();
+        assert_close(kde.bandwidth(), 3f64.powf(-0.2) * (7.0f64 / 3.0).sqrt(), 1e-15);
+        let kde = KernelDensity::fit(&data(), DensityKernel::Gaussian, Bandwidth::Silverman).unwrap();
+        assert_close(kde.bandwidth(), (4.0f64 / 9.0).powf(0.2) * 1.5 / 1.349, 1e-15);
+        let kde = KernelDensity::fit(&data(), DensityKernel::Epanechnikov, Bandwidth::Scott).unwrap();
+        assert_eq!(kde.kernel(), DensityKernel::Epanechnikov);
+        let ratio = (30.0 * PI.sqrt()).powf(0.2);
+        assert_close(kde.bandwidth(), 3f64.powf(-0.2) * (7.0f64 / 3.0).sqrt() * ratio, 1e-14);
+    }
+
+    #[test]
+    fn cross_validation() {
+        let mut rng = Rng::new(1);
+        let x = Array2::from_shape_fn((200, 1), |_| rng.normal());
+        let kde = KernelDensity::fit(&x, DensityKernel::Gaussian, Bandwidth::CrossValidation).unwrap();
+        let h = kde.bandwidth();
+        let loo = |h: f64| KernelDensity::loo_ln_likelihood(&x, DensityKernel::Gaussian, h);
+        assert!(loo(h) >= loo(1.01 * h) && loo(h) >= loo(0.99 * h));
+        assert!(h > 0.1 && h < 1.0);
+    }
+
+    #[test]
+    fn invalid_bandwidth() {
+        let constant = arr2(&[[2.0], [2.0], [2.0]]);
+        for &bandwidth in [Bandwidth::Scott, Bandwidth::Silverman, Bandwidth::CrossValidation].iter() {
+            let fit = KernelDensity::fit(&constant, DensityKernel::Gaussian, bandwidth);
+            assert_eq!(fit.err(), Some(ParamError::NotPositive("bandwidth")));
+        }
+        let one = arr2(&[[2.0]]);
+        assert!(KernelDensity::fit(&one, DensityKernel::Gaussian, Bandwidth::Scott).is_err());
+        assert!(KernelDensity::fit(&one, DensityKernel::Gaussian, Bandwidth::Fixed(1.0)).is_ok());
+        assert!(KernelDensity::fit(&data(), DensityKernel::Gaussian, Bandwidth::Fixed(0.0)).is_err());
+        assert!(KernelDensity::fit(&data(), DensityKernel::Gaussian, Bandwidth::Fixed(f64::NAN)).is_err());
+        let empty = Array2::<f64>::zeros((0, 1));
+        assert_eq!(KernelDensity::fit(&empty, DensityKernel::Gaussian, Bandwidth::Scott).err(), Some(ParamError::EmptyData));
+    }
+
+    #[test]
+    fn density_grid() {
+        let mut rng = Rng::new(2);
+        let x = Array2::from_shape_fn((500, 1), |_| rng.normal());
+        for &kernel in [DensityKernel::Gaussian, DensityKernel::Epanechnikov].iter() {
+            let kde = KernelDensity::fit(&x, kernel, Bandwidth::Silverman).unwrap();
+            let (grid, density) = kde.density_grid(2048);
+            let exact = kde.score_samples(&grid.clone().insert_axis(Axis(1)));
+            let peak = density.fold(0.0f64, |m, &v| m.max(v));
+            for (&d, &e) in density.iter().zip(exact.iter()) {
+                assert!((d - e.exp()).abs() < 1e-3 * peak);
+            }
+            let points = arr1(&[-1.0, 0.0, 0.5, 10.0]);
+            let binned = kde.score_samples_binned(&points, 2048);
+            let exact = kde.score_samples(&points.clone().insert_axis(Axis(1)));
+            for (&b, &e) in binned.iter().zip(exact.iter()).take(3) {
+                assert!((b - e).abs() < 1e-3);
+            }
+            // Outside the grid the exact sum is used.
+            assert_eq!(binned[3], exact[3]);
+        }
+    }
+
+    #[test]
+    fn sample_moments() {
+        // The sample is a data point plus a kernel offset, so the variance adds h^2 times the kernel's variance.
+        let cases = [(DensityKernel::Gaussian, 1.0), (DensityKernel::Epanechnikov, 0.2), (DensityKernel::Tophat, 1.0 / 3.0)];
+        for &(kernel, kernel_var) in cases.iter() {
+            let kde = KernelDensity::fit(&data(), kernel, Bandwidth::Fixed(0.5)).unwrap();
+            let s = kde.sample(&mut Rng::new(3), 100_000);
+            let (m, v) = (stats::mean(&s)[0], stats::var(&s, 0)[0]);
+            assert!((m - 4.0 / 3.0).abs() < 0.01);
+            assert!((v - (14.0 / 9.0 + 0.25 * kernel_var)).abs() < 0.02);
+        }
+    }
+}
@@ -0,0 +1,98 @@
+use ndarray::*;
+
+use crate::distribution::sample_index;
+use crate::float::Float;
+use crate::random::Rng;
+
+/// Squared Euclidean distance from each row of `x` to its nearest row of `centers`, and the index of that row.
+fn nearest_center<T: Float>(x: &Array2<T>, centers: ArrayView2<T>) -> (Array1<T>, Vec<usize>) {
+    let mut d2 = Array1::from_elem(x.nrows(), T::infinity());
+    let mut label = vec![0; x.nrows()];
+    for (i, row) in x.genrows().into_iter().enumerate() {
+        for (j, c) in centers.genrows().into_iter().enumerate() {
+            let d = Zip::from(&row).and(&c).fold(T::zero(), |acc, &a, &b| acc + (a - b) * (a - b));
+            if d < d2[i] {
+                d2[i] = d;
+                label[i] = j;
+            }
+        }
+    }
+    (d2, label)
+}
+
+/// k-means clustering of the rows of `x` into `k` clusters from k-means++ seeding (Arthur and Vassilvitskii).
+/// Returns the label of each row. Panics unless 1 <= k <= n_samples.
+pub fn kmeans<T: Float>(x: &Array2<T>, k: usize, rng: &mut Rng) -> Array1<usize> {
+    let (n, d) = x.dim();
+    assert!(k >= 1 && k <= n, "k must be between 1 and the number of samples");
+    let mut centers = Array2::zeros((k, d));
+    centers.row_mut(0).assign(&x.row(rng.below(n as u64) as usize));
+    for j in 1..k {
+        let (d2, _) = nearest_center(x, centers.slice(s![..j, ..]));
+        // All points coincide with a center when the total is zero; any point will do then.
+        let i = if d2.sum() > T::zero() { sample_index(rng, &d2) } else { rng.below(n as u64) as usize };
+        centers.row_mut(j).assign(&x.row(i));
+    }
+    let mut label = nearest_center(x, centers.view()).1;
+    for _ in 0..100 {
+        let mut sum = Array2::<T>::zeros((k, d));
+        let mut count = vec![0usize; k];
+        for (row, &j) in x.genrows().into_iter().zip(label.iter()) {
+            let mut s = sum.row_mut(j);
+            s += &row;
+            count[j] += 1;
+        }
+        for (j, &cj) in count.iter().enumerate() {
+            // An empty cluster keeps its center.
+            if cj > 0 {
+                let c = T::from(cj).unwrap();
+                centers.row_mut(j).assign(&sum.row(j).map(|&v| v / c));
+            }
+        }
+        let next = nearest_center(x, centers.view()).1;
+        if next == label {
+            break;
+        }
+        label = next;
+    }
+    Array1::from(label)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn separated_clusters() {
+        // Three tight clusters far apart; every seeding recovers them.
+        let centers = [[0.0, 0.0], [10.0, 0.0], [0.0, 10.0]];
+        let mut rng = Rng::new(1);
+        let x = Array2::from_shape_fn((300, 2), |(i, j)| centers[i % 3][j] + 0.1 * rng.normal::<f64>());
+        for seed in 0..10 {
+            let label = kmeans(&x, 3, &mut Rng::new(seed));
+            for i in 3..300 {
+                assert_eq!(label[i], label[i % 3]);
+            }
+            assert!(label[0] != label[1] && label[1] != label[2] && label[0] != label[2]);
+        }
+    }
+
+    #[test]
+    fn degenerate_inputs() {
+        let x = arr2(&[[1.0], [2.0], [4.0], [8.0]]);
+        let mut label = kmeans(&x, 4, &mut Rng::new(2)).to_vec();
+        label.sort_unstable();
+        assert_eq!(label, vec![0, 1, 2, 3]);
+        assert_eq!(kmeans(&x, 1, &mut Rng::new(3)), arr1(&[0, 0, 0, 0]));
+        // Coincident points leave some clusters empty.
+        let same = Array2::from_elem((5, 2), 1.5);
+        let label = kmeans(&same, 3, &mut Rng::new(4));
+        assert!(label.iter().all(|&l| l == label[0]));
+    }
+
+    #[test]
+    #[should_panic(expected = "k must be")]
+    fn too_many_clusters() {
+        kmeans(&arr2(&[[1.0], [2.0]]), 3, &mut Rng::new(5));
+    }
+}
@@ -0,0 +1,151 @@
+use crate::consts::FloatConst;
+
+/// Halley iteration for w * exp(w) = x.
+fn lambert_halley<T: FloatConst>(x: T, w: T) -> T {
+    let one = T::one();
+    let two = T::from(2).unwrap();
+    let mut w = w;
+    for _ in 0..50 {
+        let ew = w.exp();
+        let f = w * ew - x;
+        let wp1 = w + one;
+        if f == T::zero() || wp1 == T::zero() {
+            break;
+        }
+        let dw = f / (ew * wp1 - (w + two) * f / (two * wp1));
+        w -= dw;
+        if dw.abs() <= T::epsilon() * (one + w.abs()) {
+            break;
+        }
+    }
+    w
+}
+
+/// Newton iteration for w + ln|w| = `ln_x`, the logarithm of w * exp(w) = x.
+///
+/// Used away from the branch point, where w * exp(w) itself can overflow or, for tiny |x| on W_-1, become
+/// subnormal and lose the precision Halley's step needs.
+fn lambert_log_newton<T: FloatConst>(ln_x: T, w: T) -> T {
+    let one = T::one();
+    let mut w = w;
+    for _ in 0..50 {
+        let dw = (w + w.abs().ln() - ln_x) * w / (w + one);
+        w -= dw;
+        if dw.abs() <= T::epsilon() * w.abs() {
+            break;
+        }
+    }
+    w
+}
+
+/// Series in p = sqrt(2 (e x + 1)) around the branch point x = -1/e; p < 0 selects the W_-1 branch.
+fn lambert_branch_point<T: FloatConst>(p: T) -> T {
+    let c = |v: f64| T::from(v).unwrap();
+    -T::one() + p * (T::one() + p * (c(-1.0 / 3.0) + p * c(11.0 / 72.0)))
+}
+
+/// Principal branch W_0 of the Lambert W function, for x >= -1/e.
+pub fn lambert_w0<T: FloatConst>(x: T) -> T {
+    let one = T::one();
+    let e = T::from(std::f64::consts::E).unwrap();
+    let ex1 = e * x + one;
+    if ex1 < T::zero() {
+        return T::nan();
+    }
+    if ex1 == T::zero() {
+        return -one;
+    }
+    if x == T::zero() || x.is_infinite() {
+        return x;
+    }
+    if x < T::from(-0.25).unwrap() {
+        lambert_halley(x, lambert_branch_point((T::from(2).unwrap() * ex1).sqrt()))
+    } else if x < T::from(3).unwrap() {
+        lambert_halley(x, x.ln_1p() * (one - x.ln_1p() / (T::from(2).unwrap() + x.ln_1p())))
+    } else {
+        let l1 = x.ln();
+        let l2 = l1.ln();
+        lambert_log_newton(l1, l1 - l2 + l2 / l1)
+    }
+}
+
+/// Lower branch W_-1 of the Lambert W function, for -1/e <= x < 0.
+pub fn lambert_wm1<T: FloatConst>(x: T) -> T {
+    let one = T::one();
+    let e = T::from(std::f64::consts::E).unwrap();
+    let ex1 = e * x + one;
+    if ex1 < T::zero() || x > T::zero() {
+        return T::nan();
+    }
+    if ex1 == T::zero() {
+        return -one;
+    }
+    if x == T::zero() {
+        return T::neg_infinity();
+    }
+    if x < T::from(-0.25).unwrap() {
+        lambert_halley(x, lambert_branch_point(-(T::from(2).unwrap() * ex1).sqrt()))
+    } else {
+        let l1 = (-x).ln();
+        let l2 = (-l1).ln();
+        lambert_log_newton(l1, l1 - l2 + l2 / l1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::assert_close;
+
+    #[test]
+    fn principal_branch() {
+        let cases = [
+            (-0.36, -0.8060843159708176),
+            (-0.2500001, -0.35740317865552645),
+            (-0.2499999, -0.35740273370737785),
+            (-0.1, -0.11183255915896297),
+            (-1e-10, -1.0000000001000001e-10),
+            (1e-10, 9.999999999e-11),
+            (0.5, 0.35173371124919584),
+            (1.0, 0.5671432904097838),
+            (2.9999999, 1.0499088778915917),
+            (3.0000001, 1.0499089120364877),
+            (10.0, 1.7455280027406994),
+            (1e5, 9.284571428622108),
+            (1e100, 224.8431064451185),
+            (1.7e308, 703.1712364514887),
+        ];
+        for &(x, w) in cases.iter() {
+            assert_close(lambert_w0(x), w, 1e-14);
+        }
+        assert_close(lambert_w0(-0.3678794410714423), -0.9999766837418852, 1e-10);
+        assert_eq!(lambert_w0(-1.0 / std::f64::consts::E), -1.0);
+        assert_eq!(lambert_w0(0.0), 0.0);
+        assert_eq!(lambert_w0(f64::INFINITY), f64::INFINITY);
+        assert!(lambert_w0(-0.5f64).is_nan());
+    }
+
+    #[test]
+    fn lower_branch() {
+        let cases = [
+            (-5e-324, -751.0615595398791),
+            (-1e-300, -697.3227762954601),
+            (-1e-30, -73.37311031382298),
+            (-1e-5, -14.163600815810183),
+            (-0.01, -6.472775124394005),
+            (-0.2, -2.5426413577735265),
+            (-0.2500001, -2.153291617277257),
+            (-0.2499999, -2.1532931109435163),
+            (-0.3, -1.7813370234216277),
+            (-0.36, -1.2227701339785062),
+        ];
+        for &(x, w) in cases.iter() {
+            assert_close(lambert_wm1(x), w, 1e-14);
+        }
+        assert_close(lambert_wm1(-0.3678794410714423), -1.0000233166205523, 1e-10);
+        assert_eq!(lambert_wm1(-1.0 / std::f64::consts::E), -1.0);
+        assert_eq!(lambert_wm1(0.0), f64::NEG_INFINITY);
+        assert!(lambert_wm1(0.1f64).is_nan() && lambert_wm1(-0.5f64).is_nan());
+        assert_close(lambert_wm1(-1e-30f32) as f64, -73.37311031382298, 1e-6);
+    }
+}
@@ -3,6 +3,7 @@ use ndarray_linalg::*;
 
 use crate::float::Float;
 use crate::integer::Integer;
+use crate::stats;
 
 pub struct LDA<T: Float> {
     pub w: Option<Array1<T>>,
@@ -15,33 +16,29 @@ impl<T: Float> LDA<T> {
     }
 
     pub fn fit<S: Integer>(&mut self, x: &Array2<T>, t: &Array1<S>) {
-        let s = x.shape();
-        let (n, dim) = (s[0], s[1]);
+        let n = x.nrows();
         let zero = S::zero();
         let one = S::one();
         if t.shape()[0] != n {
             panic!("aaa")
         }
-        let mut m0 = Array1::<T>::zeros(dim);
-        let mut m1 = Array1::<T>::zeros(dim);
+        let mut i0 = Vec::new();
+        let mut i1 = Vec::new();
         for (i, &ti) in t.iter().enumerate() {
             if ti == zero {
-                m0 = m0 + x.slice(s![i, ..]);
+                i0.push(i);
             } else if ti == one {
-                m1 = m1 + x.slice(s![i, ..]);
+                i1.push(i);
             } else {
                 panic!("{}", ti)
             }
         }
-        let mut sw = Array2::<T>::zeros((dim, dim));
-        for (i, &ti) in t.iter().enumerate() {
-            let xm = if ti == zero {
-                (&m0 - &x.slice(s![i, ..])).into_shape((dim, 1)).unwrap()
-            } else {
-                (&m1 - &x.slice(s![i, ..])).into_shape((dim, 1)).unwrap()
-            };
-            sw = sw + xm.dot(&xm.t());
-        }
+        let x0 = x.select(Axis(0), &i0);
+        let x1 = x.select(Axis(0), &i1);
+        let (m0, m1) = (stats::mean(&x0), stats::mean(&x1));
+        // Within-class scatter, the sum of the class scatter matrices n_k * cov_k.
+        let (n0, n1) = (T::from(i0.len()).unwrap(), T::from(i1.len()).unwrap());
+        let sw = stats::cov(&x0, 0).map(|&c| c * n0) + stats::cov(&x1, 0).map(|&c| c * n1);
         let w = sw.inv().unwrap().dot(&(m1 - m0));
         let norm = T::from(w.norm_l2()).unwrap();
         self.w = Some(w.map(|&e| e / norm));
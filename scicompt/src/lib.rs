@@ -1,9 +1,23 @@
+pub mod bessel;
+pub mod beta;
 pub mod consts;
 pub mod distribution;
+pub mod erf;
 pub mod float;
+pub mod gamma;
+pub mod hypothesis;
 pub mod integer;
+pub mod kde;
 pub mod kernel;
+pub mod kmeans;
+pub mod lambert;
 pub mod lda;
 //pub mod linear;
+pub mod mcmc;
 pub mod pca;
-pub mod svm;
\ No newline at end of file
+pub mod random;
+pub mod stats;
+pub mod svm;
+#[cfg(test)]
+mod test_util;
+pub mod zeta;
\ No newline at end of file
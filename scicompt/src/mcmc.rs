@@ -0,0 +1,731 @@
+use ndarray::*;
+use ndarray_linalg::*;
+
+use crate::float::Float;
+use crate::random::Rng;
+
+/// Draws from one run of a sampler, with the warm-up discarded.
+#[derive(Clone, Debug)]
+pub struct Chain<T: Float> {
+    /// n_samples x dim
+    pub samples: Array2<T>,
+    /// Log density at each sample
+    pub ln_density: Array1<T>,
+    /// Fraction of accepted proposals, or the mean acceptance statistic for HMC and NUTS
+    pub acceptance_rate: T,
+}
+
+impl<T: Float> Chain<T> {
+    pub fn mean(&self) -> Array1<T> {
+        self.samples.mean_axis(Axis(0)).unwrap()
+    }
+
+    pub fn ess(&self) -> Array1<T> {
+        effective_sample_size(std::slice::from_ref(self))
+    }
+}
+
+fn normal_vec<T: Float>(rng: &mut Rng, d: usize) -> Array1<T> {
+    (0..d).map(|_| rng.normal()).collect()
+}
+
+/// ln of a uniform draw on (0, 1].
+fn ln_uniform<T: Float>(rng: &mut Rng) -> T {
+    (T::one() - rng.uniform::<T>()).ln()
+}
+
+/// Metropolis acceptance probability min(1, exp(diff)), zero when the proposal density is NaN.
+fn accept_prob<T: Float>(diff: T) -> T {
+    if diff.is_nan() {
+        T::zero()
+    } else {
+        diff.exp().min(T::one())
+    }
+}
+
+/// Random walk Metropolis-Hastings with a Gaussian proposal adapted during warm-up (Haario et al.).
+///
+/// The proposal covariance is lambda^2 times the running covariance of the chain, and lambda is tuned by
+/// Robbins-Monro steps towards `target_accept`. Both are frozen after warm-up, so the draws are a valid chain.
+pub struct Metropolis<T: Float> {
+    pub n_warmup: usize,
+    pub target_accept: T,
+    /// Standard deviation of the initial proposal in each coordinate
+    pub scale: T,
+}
+
+impl<T: Float> Metropolis<T> {
+    pub fn new(n_warmup: usize) -> Self {
+        Self { n_warmup, target_accept: T::from(0.234).unwrap(), scale: T::one() }
+    }
+
+    pub fn sample<F: Fn(&Array1<T>) -> T>(&self, ln_p: F, x0: &Array1<T>, n: usize, rng: &mut Rng) -> Chain<T> {
+        let d = x0.len();
+        let one = T::one();
+        let mut x = x0.to_owned();
+        let mut lp = ln_p(&x);
+        let ln_lambda0 = (T::from(2.38).unwrap() / T::from(d).unwrap().sqrt()).ln();
+        let mut ln_lambda = ln_lambda0;
+        let mut empirical = false;
+        let mut l: Array2<T> = Array2::<T>::eye(d).map(|&e| e * self.scale);
+        // Running mean and sum of squared deviations of the warm-up draws
+        let mut mean = x.clone();
+        let mut m2 = Array2::<T>::zeros((d, d));
+        let mut samples = Array2::zeros((n, d));
+        let mut ln_density = Array1::zeros(n);
+        let mut accepted = 0;
+        for t in 0..self.n_warmup + n {
+            let lambda = ln_lambda.exp();
+            let step = l.dot(&normal_vec(rng, d));
+            let y = Zip::from(&x).and(&step).apply_collect(|&xi, &si| xi + lambda * si);
+            let lpy = ln_p(&y);
+            let alpha = accept_prob(lpy - lp);
+            if rng.uniform::<T>() < alpha {
+                x = y;
+                lp = lpy;
+                if t >= self.n_warmup {
+                    accepted += 1;
+                }
+            }
+            if t < self.n_warmup {
+                let k = T::from(t + 2).unwrap();
+                ln_lambda += (alpha - self.target_accept) / (k - one).powf(T::from(0.6).unwrap());
+                let dx = &x - &mean;
+                mean = Zip::from(&mean).and(&dx).apply_collect(|&m, &e| m + e / k);
+                let dx2 = &x - &mean;
+                for ((i, j), m) in m2.indexed_iter_mut() {
+                    *m += dx[i] * dx2[j];
+                }
+                if (t + 1) % 50 == 0 && t + 2 > 10 * d {
+                    let cov = m2.map(|&v| v / (k - one));
+                    let eps = T::from(1e-10).unwrap() * cov.diag().sum() / T::from(d).unwrap();
+                    if let Ok(c) = (&cov + &Array2::<T>::eye(d).map(|&e| e * eps)).cholesky(UPLO::Lower) {
+                        l = c;
+                        // The empirical covariance already has the scale of the target.
+                        if !empirical {
+                            ln_lambda = ln_lambda0;
+                            empirical = true;
+                        }
+                    }
+                }
+            } else {
+                samples.row_mut(t - self.n_warmup).assign(&x);
+                ln_density[t - self.n_warmup] = lp;
+            }
+        }
+        Chain { samples, ln_density, acceptance_rate: T::from(accepted).unwrap() / T::from(n.max(1)).unwrap() }
+    }
+}
+
+/// Univariate slice sampling (Neal 2003) applied to each coordinate in turn, with stepping out and shrinkage.
+///
+/// During warm-up the initial interval width of each coordinate is set to twice its mean absolute move. Every
+/// draw is accepted, so `acceptance_rate` is one.
+pub struct SliceSampler<T: Float> {
+    pub n_warmup: usize,
+    /// Initial interval width in each coordinate
+    pub width: T,
+    /// Maximum number of steps when stepping out
+    pub max_steps: usize,
+}
+
+impl<T: Float> SliceSampler<T> {
+    pub fn new(n_warmup: usize) -> Self {
+        Self { n_warmup, width: T::one(), max_steps: 100 }
+    }
+
+    pub fn sample<F: Fn(&Array1<T>) -> T>(&self, ln_p: F, x0: &Array1<T>, n: usize, rng: &mut Rng) -> Chain<T> {
+        let d = x0.len();
+        let mut x = x0.to_owned();
+        let mut lp = ln_p(&x);
+        let mut width = Array1::from_elem(d, self.width);
+        let mut moved = Array1::<T>::zeros(d);
+        let mut samples = Array2::zeros((n, d));
+        let mut ln_density = Array1::zeros(n);
+        for t in 0..self.n_warmup + n {
+            for i in 0..d {
+                let xi = x[i];
+                let ln_y = lp + ln_uniform(rng);
+                let w = width[i];
+                let mut eval = |v: T| {
+                    x[i] = v;
+                    ln_p(&x)
+                };
+                let mut lo = xi - w * rng.uniform();
+                let mut hi = lo + w;
+                let mut j = (T::from(self.max_steps).unwrap() * rng.uniform()).floor().to_usize().unwrap();
+                let mut k = self.max_steps - 1 - j;
+                while j > 0 && eval(lo) > ln_y {
+                    lo -= w;
+                    j -= 1;
+                }
+                while k > 0 && eval(hi) > ln_y {
+                    hi += w;
+                    k -= 1;
+                }
+                loop {
+                    let v = lo + (hi - lo) * rng.uniform();
+                    let lv = eval(v);
+                    if lv > ln_y {
+                        lp = lv;
+                        break;
+                    }
+                    if v < xi {
+                        lo = v;
+                    } else {
+                        hi = v;
+                    }
+                }
+                if t < self.n_warmup {
+                    moved[i] += (x[i] - xi).abs();
+                    let mean_move = moved[i] / T::from(t + 1).unwrap();
+                    if t >= 10 && mean_move > T::zero() {
+                        width[i] = mean_move + mean_move;
+                    }
+                }
+            }
+            if t >= self.n_warmup {
+                samples.row_mut(t - self.n_warmup).assign(&x);
+                ln_density[t - self.n_warmup] = lp;
+            }
+        }
+        Chain { samples, ln_density, acceptance_rate: T::one() }
+    }
+}
+
+/// Log density and its gradient, with a diagonal inverse mass matrix.
+struct Hamiltonian<'a, T: Float, F, G> {
+    ln_p: &'a F,
+    grad: &'a G,
+    inv_metric: Array1<T>,
+}
+
+/// Position, momentum, log density and gradient at a point of a trajectory.
+#[derive(Clone)]
+struct State<T: Float> {
+    x: Array1<T>,
+    r: Array1<T>,
+    lp: T,
+    g: Array1<T>,
+}
+
+impl<'a, T: Float, F: Fn(&Array1<T>) -> T, G: Fn(&Array1<T>) -> Array1<T>> Hamiltonian<'a, T, F, G> {
+    fn momentum(&self, rng: &mut Rng) -> Array1<T> {
+        self.inv_metric.map(|&m| rng.normal::<T>() / m.sqrt())
+    }
+
+    fn kinetic(&self, r: &Array1<T>) -> T {
+        T::from(0.5).unwrap() * Zip::from(r).and(&self.inv_metric).fold(T::zero(), |acc, &ri, &mi| acc + mi * ri * ri)
+    }
+
+    /// ln p(x) minus the kinetic energy, the log of the joint density.
+    fn joint(&self, s: &State<T>) -> T {
+        s.lp - self.kinetic(&s.r)
+    }
+
+    fn leapfrog(&self, s: &State<T>, eps: T) -> State<T> {
+        let half = T::from(0.5).unwrap() * eps;
+        let r = Zip::from(&s.r).and(&s.g).apply_collect(|&ri, &gi| ri + half * gi);
+        let x = Zip::from(&s.x).and(&r).and(&self.inv_metric).apply_collect(|&xi, &ri, &mi| xi + eps * mi * ri);
+        let g = (self.grad)(&x);
+        let r = Zip::from(&r).and(&g).apply_collect(|&ri, &gi| ri + half * gi);
+        let lp = (self.ln_p)(&x);
+        State { x, r, lp, g }
+    }
+
+    /// Doubles the step size from `eps` until the acceptance probability of one leapfrog step crosses one half.
+    fn initial_step_size(&self, x: &Array1<T>, eps: T, rng: &mut Rng) -> T {
+        let two = T::from(2).unwrap();
+        let half_ln = T::from(0.5).unwrap().ln();
+        let s = State { x: x.to_owned(), r: self.momentum(rng), lp: (self.ln_p)(x), g: (self.grad)(x) };
+        let joint0 = self.joint(&s);
+        let mut eps = eps;
+        let diff = |eps: T| {
+            let d = self.joint(&self.leapfrog(&s, eps)) - joint0;
+            if d.is_nan() {
+                T::neg_infinity()
+            } else {
+                d
+            }
+        };
+        let up = diff(eps) > half_ln;
+        for _ in 0..100 {
+            let next = if up { eps * two } else { eps / two };
+            if (diff(next) > half_ln) != up {
+                return if up { eps } else { next };
+            }
+            eps = next;
+        }
+        eps
+    }
+
+    fn no_u_turn(&self, minus: &State<T>, plus: &State<T>) -> bool {
+        let dx = &plus.x - &minus.x;
+        let v = |r: &Array1<T>| {
+            Zip::from(&dx).and(r).and(&self.inv_metric).fold(T::zero(), |acc, &a, &b, &m| acc + a * b * m)
+        };
+        v(&minus.r) >= T::zero() && v(&plus.r) >= T::zero()
+    }
+}
+
+/// Dual averaging of the log step size towards a target acceptance statistic (Hoffman and Gelman).
+struct DualAveraging<T: Float> {
+    mu: T,
+    h_bar: T,
+    ln_eps_bar: T,
+    m: usize,
+}
+
+impl<T: Float> DualAveraging<T> {
+    fn new(eps: T) -> Self {
+        Self { mu: (T::from(10).unwrap() * eps).ln(), h_bar: T::zero(), ln_eps_bar: T::zero(), m: 0 }
+    }
+
+    /// Returns the next step size.
+    fn update(&mut self, target: T, accept: T) -> T {
+        self.m += 1;
+        let m = T::from(self.m).unwrap();
+        let t0 = T::from(10).unwrap();
+        let w = T::one() / (m + t0);
+        self.h_bar = (T::one() - w) * self.h_bar + w * (target - accept);
+        let ln_eps = self.mu - m.sqrt() / T::from(0.05).unwrap() * self.h_bar;
+        let eta = m.powf(T::from(-0.75).unwrap());
+        self.ln_eps_bar = eta * ln_eps + (T::one() - eta) * self.ln_eps_bar;
+        ln_eps.exp()
+    }
+
+    fn final_step_size(&self) -> T {
+        self.ln_eps_bar.exp()
+    }
+}
+
+/// Warm-up and sampling loop shared by HMC and NUTS. `transition` moves from a state with step size `eps` and returns
+/// the new state and the acceptance statistic.
+///
+/// The step size is tuned by dual averaging throughout warm-up. The diagonal mass matrix is set from the variance of
+/// the draws between 15% and 75% of warm-up, after which the step size adaptation restarts.
+#[allow(clippy::too_many_arguments)]
+fn run_hamiltonian<T, F, G, K>(
+    ln_p: &F,
+    grad: &G,
+    x0: &Array1<T>,
+    n: usize,
+    n_warmup: usize,
+    target: T,
+    eps0: T,
+    rng: &mut Rng,
+    transition: K,
+) -> Chain<T>
+where
+    T: Float,
+    F: Fn(&Array1<T>) -> T,
+    G: Fn(&Array1<T>) -> Array1<T>,
+    K: Fn(&Hamiltonian<T, F, G>, &State<T>, T, &mut Rng) -> (State<T>, T),
+{
+    let d = x0.len();
+    let mut ham = Hamiltonian { ln_p, grad, inv_metric: Array1::from_elem(d, T::one()) };
+    let mut s = State { x: x0.to_owned(), r: Array1::zeros(d), lp: ln_p(x0), g: grad(x0) };
+    let mut eps = if n_warmup > 0 { ham.initial_step_size(x0, eps0, rng) } else { eps0 };
+    let mut da = DualAveraging::new(eps);
+    let (w_start, w_end) = (n_warmup * 3 / 20, n_warmup * 3 / 4);
+    let mut window = Vec::new();
+    let mut samples = Array2::zeros((n, d));
+    let mut ln_density = Array1::zeros(n);
+    let mut accept_sum = T::zero();
+    for t in 0..n_warmup + n {
+        let (next, accept) = transition(&ham, &s, eps, rng);
+        s = next;
+        if t < n_warmup {
+            eps = da.update(target, accept);
+            if t >= w_start && t < w_end {
+                window.push(s.x.clone());
+            }
+            if t + 1 == w_end && window.len() > 10 {
+                let k = T::from(window.len()).unwrap();
+                let mean = window.iter().fold(Array1::<T>::zeros(d), |acc, x| acc + x).map(|&v| v / k);
+                let var = window
+                    .iter()
+                    .fold(Array1::zeros(d), |acc: Array1<T>, x| acc + (x - &mean).map(|&v| v * v))
+                    .map(|&v| v / (k - T::one()));
+                // Shrink towards one, as Stan does, so a short window cannot produce a degenerate metric.
+                let five = T::from(5).unwrap();
+                ham.inv_metric = var.map(|&v| (k / (k + five)) * v + T::from(1e-3).unwrap() * five / (k + five));
+                eps = ham.initial_step_size(&s.x, eps, rng);
+                da = DualAveraging::new(eps);
+            }
+            if t + 1 == n_warmup {
+                eps = da.final_step_size();
+            }
+        } else {
+            samples.row_mut(t - n_warmup).assign(&s.x);
+            ln_density[t - n_warmup] = s.lp;
+            accept_sum += accept;
+        }
+    }
+    Chain { samples, ln_density, acceptance_rate: accept_sum / T::from(n.max(1)).unwrap() }
+}
+
+/// Hamiltonian Monte Carlo with a fixed number of leapfrog steps. The step size and a diagonal mass matrix are tuned
+/// during warm-up.
+pub struct Hmc<T: Float> {
+    pub n_warmup: usize,
+    pub n_leapfrog: usize,
+    pub target_accept: T,
+    /// Step size before adaptation, or the step size used when there is no warm-up
+    pub step_size: T,
+    /// Each iteration uses a step size drawn uniformly from step_size * (1 +- jitter), so that a fixed trajectory
+    /// length cannot lock onto the period of the target.
+    pub jitter: T,
+}
+
+impl<T: Float> Hmc<T> {
+    pub fn new(n_warmup: usize, n_leapfrog: usize) -> Self {
+        Self {
+            n_warmup,
+            n_leapfrog,
+            target_accept: T::from(0.8).unwrap(),
+            step_size: T::from(0.1).unwrap(),
+            jitter: T::from(0.2).unwrap(),
+        }
+    }
+
+    /// `grad` is the gradient of `ln_p`.
+    pub fn sample<F, G>(&self, ln_p: F, grad: G, x0: &Array1<T>, n: usize, rng: &mut Rng) -> Chain<T>
+    where
+        F: Fn(&Array1<T>) -> T,
+        G: Fn(&Array1<T>) -> Array1<T>,
+    {
+        let (n_leapfrog, jitter) = (self.n_leapfrog, self.jitter);
+        run_hamiltonian(
+            &ln_p,
+            &grad,
+            x0,
+            n,
+            self.n_warmup,
+            self.target_accept,
+            self.step_size,
+            rng,
+            |ham, s, eps, rng| {
+                let eps = eps * (T::one() + jitter * (rng.uniform::<T>() * T::from(2).unwrap() - T::one()));
+                let mut cur = State { r: ham.momentum(rng), ..s.clone() };
+                let joint0 = ham.joint(&cur);
+                let mut prop = cur.clone();
+                for _ in 0..n_leapfrog {
+                    prop = ham.leapfrog(&prop, eps);
+                }
+                let alpha = accept_prob(ham.joint(&prop) - joint0);
+                if rng.uniform::<T>() < alpha {
+                    cur = prop;
+                }
+                (cur, alpha)
+            },
+        )
+    }
+}
+
+/// Subtree built by NUTS: its leftmost and rightmost states, the proposal drawn from it, the number of states inside
+/// the slice, whether it is still valid, and the summed acceptance statistic over `n_alpha` states.
+struct Tree<T: Float> {
+    minus: State<T>,
+    plus: State<T>,
+    prop: State<T>,
+    n: usize,
+    valid: bool,
+    alpha: T,
+    n_alpha: usize,
+}
+
+/// The No-U-Turn sampler (Hoffman and Gelman, Algorithm 6), with the step size and a diagonal mass matrix tuned
+/// during warm-up.
+pub struct Nuts<T: Float> {
+    pub n_warmup: usize,
+    pub target_accept: T,
+    /// The trajectory has at most 2^max_depth leapfrog steps.
+    pub max_depth: usize,
+    /// Step size before adaptation, or the step size used when there is no warm-up
+    pub step_size: T,
+}
+
+impl<T: Float> Nuts<T> {
+    pub fn new(n_warmup: usize) -> Self {
+        Self { n_warmup, target_accept: T::from(0.8).unwrap(), max_depth: 10, step_size: T::from(0.1).unwrap() }
+    }
+
+    /// `grad` is the gradient of `ln_p`.
+    pub fn sample<F, G>(&self, ln_p: F, grad: G, x0: &Array1<T>, n: usize, rng: &mut Rng) -> Chain<T>
+    where
+        F: Fn(&Array1<T>) -> T,
+        G: Fn(&Array1<T>) -> Array1<T>,
+    {
+        let max_depth = self.max_depth;
+        run_hamiltonian(
+            &ln_p,
+            &grad,
+            x0,
+            n,
+            self.n_warmup,
+            self.target_accept,
+            self.step_size,
+            rng,
+            |ham, s, eps, rng| {
+                let start = State { r: ham.momentum(rng), ..s.clone() };
+                let joint0 = ham.joint(&start);
+                let ln_u = joint0 + ln_uniform(rng);
+                let mut minus = start.clone();
+                let mut plus = start;
+                let mut cur = s.clone();
+                let mut n = 1;
+                let mut accept = T::zero();
+                for depth in 0..max_depth {
+                    let forward = rng.uniform::<T>() < T::from(0.5).unwrap();
+                    let tree = if forward {
+                        let t = Self::build_tree(ham, &plus, ln_u, eps, depth, joint0, rng);
+                        plus = t.plus.clone();
+                        t
+                    } else {
+                        let t = Self::build_tree(ham, &minus, ln_u, -eps, depth, joint0, rng);
+                        minus = t.minus.clone();
+                        t
+                    };
+                    accept = tree.alpha / T::from(tree.n_alpha).unwrap();
+                    if !tree.valid {
+                        break;
+                    }
+                    if rng.uniform::<T>() < T::from(tree.n).unwrap() / T::from(n).unwrap() {
+                        cur = State { r: s.r.clone(), ..tree.prop };
+                    }
+                    n += tree.n;
+                    if !ham.no_u_turn(&minus, &plus) {
+                        break;
+                    }
+                }
+                (cur, accept)
+            },
+        )
+    }
+
+    /// Builds a subtree of 2^depth leapfrog steps of size `eps` (negative to go backwards) from `s`.
+    fn build_tree<F, G>(
+        ham: &Hamiltonian<T, F, G>,
+        s: &State<T>,
+        ln_u: T,
+        eps: T,
+        depth: usize,
+        joint0: T,
+        rng: &mut Rng,
+    ) -> Tree<T>
+    where
+        F: Fn(&Array1<T>) -> T,
+        G: Fn(&Array1<T>) -> Array1<T>,
+    {
+        if depth == 0 {
+            let next = ham.leapfrog(s, eps);
+            let joint = ham.joint(&next);
+            let n = if ln_u <= joint { 1 } else { 0 };
+            // Trajectories whose energy error exceeds 1000 have diverged.
+            let valid = ln_u < joint + T::from(1000).unwrap();
+            let alpha = accept_prob(joint - joint0);
+            return Tree { minus: next.clone(), plus: next.clone(), prop: next, n, valid, alpha, n_alpha: 1 };
+        }
+        let mut tree = Self::build_tree(ham, s, ln_u, eps, depth - 1, joint0, rng);
+        if tree.valid {
+            let other = if eps < T::zero() {
+                let t = Self::build_tree(ham, &tree.minus, ln_u, eps, depth - 1, joint0, rng);
+                tree.minus = t.minus.clone();
+                t
+            } else {
+                let t = Self::build_tree(ham, &tree.plus, ln_u, eps, depth - 1, joint0, rng);
+                tree.plus = t.plus.clone();
+                t
+            };
+            let total = tree.n + other.n;
+            if total > 0 && rng.uniform::<T>() < T::from(other.n).unwrap() / T::from(total).unwrap() {
+                tree.prop = other.prop;
+            }
+            tree.alpha += other.alpha;
+            tree.n_alpha += other.n_alpha;
+            tree.valid = other.valid && ham.no_u_turn(&tree.minus, &tree.plus);
+            tree.n = total;
+        }
+        tree
+    }
+}
+
+/// Within-chain variance W and the pooled variance estimate var+ over chains of equal length.
+fn chain_variances<T: Float>(chains: &[ArrayView1<T>]) -> (T, T) {
+    let one = T::one();
+    let n = T::from(chains[0].len()).unwrap();
+    let m = T::from(chains.len()).unwrap();
+    let means: Vec<T> = chains.iter().map(|c| c.mean().unwrap()).collect();
+    let w = chains.iter().map(|c| c.var_axis(Axis(0), one).into_scalar()).fold(T::zero(), |a, v| a + v) / m;
+    let grand = means.iter().fold(T::zero(), |a, &v| a + v) / m;
+    let b_over_n = if chains.len() > 1 {
+        means.iter().fold(T::zero(), |a, &v| a + (v - grand) * (v - grand)) / (m - one)
+    } else {
+        T::zero()
+    };
+    (w, (n - one) / n * w + b_over_n)
+}
+
+/// Draws of coordinate `k` of each chain, truncated to the shortest chain.
+fn coordinate<T: Float>(chains: &[Chain<T>], k: usize) -> Vec<ArrayView1<'_, T>> {
+    let n = chains.iter().map(|c| c.samples.nrows()).min().unwrap();
+    chains.iter().map(|c| c.samples.slice(s![..n, k])).collect()
+}
+
+/// Effective sample size of each coordinate over one or more chains, using the multi-chain autocorrelation and
+/// Geyer's initial monotone sequence estimator (as in Stan). Empty when there are no chains.
+pub fn effective_sample_size<T: Float>(chains: &[Chain<T>]) -> Array1<T> {
+    let d = chains.first().map_or(0, |c| c.samples.ncols());
+    (0..d)
+        .map(|k| {
+            let cs = coordinate(chains, k);
+            let n = cs[0].len();
+            let m = T::from(cs.len()).unwrap();
+            let nf = T::from(n).unwrap();
+            let (w, var_plus) = chain_variances(&cs);
+            if var_plus.is_nan() || var_plus <= T::zero() {
+                return T::nan();
+            }
+            let centered: Vec<Array1<T>> = cs
+                .iter()
+                .map(|c| {
+                    let mu = c.mean().unwrap();
+                    c.map(|&v| v - mu)
+                })
+                .collect();
+            // Autocovariance at `lag` averaged over the chains, with the biased (divide by n) estimator.
+            let autocov = |lag: usize| {
+                centered.iter().fold(T::zero(), |acc, c| acc + c.slice(s![..n - lag]).dot(&c.slice(s![lag..])) / nf) / m
+            };
+            let rho = |lag: usize| T::one() - (w * (nf - T::one()) / nf - autocov(lag)) / var_plus;
+            let mut tau = -T::one();
+            let mut prev = T::infinity();
+            let mut lag = 0;
+            while lag + 1 < n {
+                let mut pair = rho(lag) + rho(lag + 1);
+                if pair < T::zero() {
+                    break;
+                }
+                pair = pair.min(prev);
+                prev = pair;
+                tau += pair + pair;
+                lag += 2;
+            }
+            m * nf / tau.max(T::one() / (m * nf).log10().max(T::one()))
+        })
+        .collect()
+}
+
+/// Split R-hat of each coordinate: every chain is split in half and the between- and within-chain variances of
+/// the halves are compared. Values near one indicate convergence. Empty when there are no chains.
+pub fn r_hat<T: Float>(chains: &[Chain<T>]) -> Array1<T> {
+    let d = chains.first().map_or(0, |c| c.samples.ncols());
+    (0..d)
+        .map(|k| {
+            let cs = coordinate(chains, k);
+            let half = cs[0].len() / 2;
+            let split: Vec<ArrayView1<T>> =
+                cs.iter().flat_map(|c| vec![c.slice_move(s![..half]), c.slice_move(s![half..2 * half])]).collect();
+            let (w, var_plus) = chain_variances(&split);
+            (var_plus / w).sqrt()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::assert_close;
+
+    fn chain(samples: Array2<f64>) -> Chain<f64> {
+        let n = samples.nrows();
+        Chain { samples, ln_density: Array1::zeros(n), acceptance_rate: 1.0 }
+    }
+
+    /// Precision matrix times (x - mean) for a Gaussian with mean (1, -2), standard deviations (1, 3) and
+    /// correlation 0.8.
+    fn precision_times(x: &Array1<f64>) -> Array1<f64> {
+        let (a, b) = (x[0] - 1.0, (x[1] + 2.0) / 3.0);
+        let c = 1.0 / (1.0 - 0.64);
+        arr1(&[c * (a - 0.8 * b), c * (b - 0.8 * a) / 3.0])
+    }
+
+    fn ln_p(x: &Array1<f64>) -> f64 {
+        let g = precision_times(x);
+        -0.5 * ((x[0] - 1.0) * g[0] + (x[1] + 2.0) * g[1])
+    }
+
+    fn grad(x: &Array1<f64>) -> Array1<f64> {
+        -precision_times(x)
+    }
+
+    fn check_moments(c: &Chain<f64>, tol: f64) {
+        let m = c.mean();
+        let v = c.samples.var_axis(Axis(0), 1.0);
+        let xc = &c.samples - &m;
+        let r = xc.column(0).dot(&xc.column(1)) / (c.samples.nrows() as f64 * (v[0] * v[1]).sqrt());
+        assert!((m[0] - 1.0).abs() < tol && (m[1] + 2.0).abs() < 3.0 * tol, "mean {}", m);
+        assert!((v[0] - 1.0).abs() < 2.0 * tol && (v[1] - 9.0).abs() < 18.0 * tol, "var {}", v);
+        assert!((r - 0.8).abs() < tol, "corr {}", r);
+    }
+
+    #[test]
+    fn sampler_moments() {
+        let x0 = arr1(&[0.0, 0.0]);
+        let c = Metropolis::new(2000).sample(&ln_p, &x0, 50_000, &mut Rng::new(1));
+        assert!(c.acceptance_rate > 0.15 && c.acceptance_rate < 0.35);
+        check_moments(&c, 0.05);
+        let c = SliceSampler::new(500).sample(&ln_p, &x0, 20_000, &mut Rng::new(2));
+        assert_eq!(c.acceptance_rate, 1.0);
+        check_moments(&c, 0.05);
+        let c = Hmc::new(1000, 10).sample(&ln_p, &grad, &x0, 10_000, &mut Rng::new(3));
+        assert!(c.acceptance_rate > 0.6);
+        check_moments(&c, 0.05);
+        let c = Nuts::new(1000).sample(&ln_p, &grad, &x0, 10_000, &mut Rng::new(4));
+        assert!((c.acceptance_rate - 0.8).abs() < 0.1);
+        check_moments(&c, 0.05);
+        // The stored log densities belong to the stored draws.
+        for (x, &lp) in c.samples.genrows().into_iter().zip(c.ln_density.iter()).take(10) {
+            assert_close(lp, ln_p(&x.to_owned()), 1e-12);
+        }
+    }
+
+    #[test]
+    fn ess_of_ar1() {
+        // An AR(1) series with coefficient phi has integrated autocorrelation time (1 + phi) / (1 - phi).
+        let mut rng = Rng::new(5);
+        let n = 100_000;
+        let cases = [(0.0, 1.0), (0.5, 3.0), (0.9, 19.0), (-0.5, 1.0 / 3.0)];
+        for &(phi, tau) in cases.iter() {
+            let mut x = Array2::zeros((n, 1));
+            let mut v: f64 = rng.normal::<f64>() / (1.0 - phi * phi).sqrt();
+            for i in 0..n {
+                x[[i, 0]] = v;
+                v = phi * v + rng.normal::<f64>();
+            }
+            let ess = chain(x).ess()[0];
+            assert!((ess * tau / n as f64 - 1.0).abs() < 0.1, "phi {} ess {}", phi, ess);
+        }
+    }
+
+    #[test]
+    fn split_r_hat() {
+        // Halves [1, 2] and [3, 4]: W = 1 / 2 and var+ = W / 2 + 2 = 9 / 4.
+        let c = chain(arr2(&[[1.0], [2.0], [3.0], [4.0]]));
+        assert_close(r_hat(std::slice::from_ref(&c))[0], 4.5f64.sqrt(), 1e-15);
+        let mut rng = Rng::new(6);
+        let mut draws = |shift: f64| chain(Array2::from_shape_fn((2000, 2), |_| shift + rng.normal::<f64>()));
+        let same = [draws(0.0), draws(0.0), draws(0.0), draws(0.0)];
+        let r = r_hat(&same);
+        assert!(r.iter().all(|&v| v < 1.01), "{}", r);
+        assert!(effective_sample_size(&same).iter().all(|&v| (v / 8000.0 - 1.0).abs() < 0.1));
+        let apart = [draws(0.0), draws(0.0), draws(0.0), draws(2.0)];
+        assert!(r_hat(&apart).iter().all(|&v| v > 1.1));
+        // Chains of different lengths are truncated to the shortest.
+        let short = chain(same[0].samples.slice(s![..1000, ..]).to_owned());
+        assert_eq!(r_hat(&[short.clone(), same[1].clone()]).len(), 2);
+        assert_eq!(r_hat::<f64>(&[]).len(), 0);
+        assert_eq!(effective_sample_size::<f64>(&[]).len(), 0);
+    }
+}
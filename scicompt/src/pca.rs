@@ -2,6 +2,7 @@ use ndarray::*;
 use ndarray_linalg::*;
 
 use crate::float::Float;
+use crate::stats;
 
 pub struct PCA<T: Float> {
     /// n_components x n_features
@@ -22,18 +23,7 @@ impl<T: Float> PCA<T> {
     }
 
     pub fn fit(&mut self, x: &Array2<T>, n_components: usize) {
-        let shape = x.shape();
-        let row = shape[0];
-        let col = shape[1];
-        let e = x.mean_axis(Axis(0)).unwrap();
-        let mut s: Array2<T> = Array2::zeros((col, col));
-        let n_1 = T::from_usize(row - 1).unwrap();
-
-        for r in 0..col {
-            for c in 0..col {
-                s[[r, c]] = x.slice(s![.., r]).iter().zip(x.slice(s![.., c]).iter()).map(|(&er, &ec)| (er - e[r]) * (ec - e[c])).sum::<T>() / n_1;
-            }
-        }
+        let s = stats::cov(x, 1);
         match s.eigh(UPLO::Upper) {
             Ok((val, vec)) => {
                 self.eigvecs = Some(vec);
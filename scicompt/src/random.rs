@@ -0,0 +1,161 @@
+use crate::float::Float;
+
+/// xoshiro256** pseudo random number generator.
+///
+/// The stream is fully determined by the seed. `next_u64`, `below` and `uniform` are bit-for-bit reproducible
+/// across runs and platforms. `normal` and `gamma` go through the platform's `ln` and `powf`, which may differ in
+/// the last bit between math libraries, so their draws are only reproducible to rounding; in rare cases `gamma`
+/// can also take a different rejection branch and consume the stream differently.
+#[derive(Clone, Debug)]
+pub struct Rng {
+    s: [u64; 4],
+}
+
+impl Rng {
+    /// Seeds the state with splitmix64, as recommended by the xoshiro authors.
+    pub fn new(seed: u64) -> Self {
+        let mut z = seed;
+        let mut s = [0; 4];
+        for si in s.iter_mut() {
+            z = z.wrapping_add(0x9e37_79b9_7f4a_7c15);
+            let mut x = z;
+            x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+            x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+            *si = x ^ (x >> 31);
+        }
+        Self { s }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        let s = &mut self.s;
+        let result = s[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+        let t = s[1] << 17;
+        s[2] ^= s[0];
+        s[3] ^= s[1];
+        s[1] ^= s[2];
+        s[0] ^= s[3];
+        s[2] ^= t;
+        s[3] = s[3].rotate_left(45);
+        result
+    }
+
+    /// Uniform integer in [0, n). Panics if `n` is zero.
+    pub fn below(&mut self, n: u64) -> u64 {
+        assert!(n > 0, "n must be positive");
+        // Rejection sampling to avoid modulo bias.
+        let zone = u64::MAX - u64::MAX % n;
+        loop {
+            let x = self.next_u64();
+            if x < zone {
+                return x % n;
+            }
+        }
+    }
+
+    /// Uniform sample in [0, 1).
+    pub fn uniform<T: Float>(&mut self) -> T {
+        // Use as many random bits as the mantissa holds so the result is exact in T.
+        let bits = (-T::epsilon().log2()).to_u32().unwrap() + 1;
+        let x = self.next_u64() >> (64 - bits);
+        T::from(x).unwrap() / T::from(2).unwrap().powi(bits as i32)
+    }
+
+    /// Standard normal sample (Marsaglia polar method).
+    pub fn normal<T: Float>(&mut self) -> T {
+        let one = T::one();
+        let two = T::from(2).unwrap();
+        loop {
+            let u = two * self.uniform::<T>() - one;
+            let v = two * self.uniform::<T>() - one;
+            let s = u * u + v * v;
+            if s > T::zero() && s < one {
+                return u * (-two * s.ln() / s).sqrt();
+            }
+        }
+    }
+
+    /// Gamma(shape, 1) sample (Marsaglia and Tsang).
+    pub fn gamma<T: Float>(&mut self, shape: T) -> T {
+        let one = T::one();
+        if shape < one {
+            let u: T = self.uniform();
+            return self.gamma(shape + one) * u.powf(one / shape);
+        }
+        let d = shape - one / T::from(3).unwrap();
+        let c = one / (T::from(9).unwrap() * d).sqrt();
+        loop {
+            let x: T = self.normal();
+            let v = one + c * x;
+            if v <= T::zero() {
+                continue;
+            }
+            let v = v * v * v;
+            let u: T = self.uniform();
+            let x2 = x * x;
+            if u < one - T::from(0.0331).unwrap() * x2 * x2 {
+                return d * v;
+            }
+            if u.ln() < T::from(0.5).unwrap() * x2 + d * (one - v + v.ln()) {
+                return d * v;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Reference streams computed independently from the published xoshiro256** and splitmix64 algorithms.
+
+    #[test]
+    fn golden_u64() {
+        let mut rng = Rng::new(42);
+        let expected = [0x1578_0b2e_0c2e_c716, 0x6104_d986_6d11_3a7e, 0xae17_5332_39e4_99a1, 0xecb8_ad47_03b3_60a1];
+        for &e in expected.iter() {
+            assert_eq!(rng.next_u64(), e);
+        }
+    }
+
+    #[test]
+    fn golden_uniform_and_below() {
+        let mut rng = Rng::new(42);
+        for &e in [0.08386297105988216, 0.3789802506626686, 0.6800434110281394].iter() {
+            assert_eq!(rng.uniform::<f64>(), e);
+        }
+        let mut rng = Rng::new(1);
+        let draws: Vec<u64> = (0..8).map(|_| rng.below(10)).collect();
+        assert_eq!(draws, vec![7, 2, 0, 3, 1, 2, 6, 9]);
+    }
+
+    #[test]
+    #[should_panic(expected = "n must be positive")]
+    fn below_zero() {
+        Rng::new(1).below(0);
+    }
+
+    #[test]
+    fn golden_normal_and_gamma() {
+        let mut rng = Rng::new(7);
+        let close = |a: f64, b: f64| (a - b).abs() <= 1e-14 * b.abs();
+        for &e in [0.9643618527255184, -0.3039301238656567, 0.30479435832638674].iter() {
+            assert!(close(rng.normal(), e));
+        }
+        assert!(close(rng.gamma(2.5), 0.5034810795423466));
+        assert!(close(rng.gamma(2.5), 1.9961417503732015));
+        assert!(close(rng.gamma(0.3), 0.007510663550444678));
+    }
+
+    #[test]
+    fn moments() {
+        let mut rng = Rng::new(3);
+        let n = 200_000;
+        let x: Vec<f64> = (0..n).map(|_| rng.normal()).collect();
+        let mean = x.iter().sum::<f64>() / n as f64;
+        let var = x.iter().map(|v| (v - mean) * (v - mean)).sum::<f64>() / n as f64;
+        assert!(mean.abs() < 0.01 && (var - 1.0).abs() < 0.01);
+        let g: Vec<f64> = (0..n).map(|_| rng.gamma(0.5)).collect();
+        let mean = g.iter().sum::<f64>() / n as f64;
+        assert!((mean - 0.5).abs() < 0.01);
+    }
+}
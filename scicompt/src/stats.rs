@@ -0,0 +1,281 @@
+use ndarray::*;
+
+use crate::float::Float;
+
+/// How `quantile` picks a value when the quantile falls between two order statistics `lo <= hi`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Interpolation {
+    /// lo + (hi - lo) * fraction
+    Linear,
+    Lower,
+    Higher,
+    /// Whichever of lo and hi is closer, hi on a tie.
+    Nearest,
+    /// (lo + hi) / 2
+    Midpoint,
+}
+
+/// Column means. Panics if `x` has no rows.
+pub fn mean<T: Float>(x: &Array2<T>) -> Array1<T> {
+    x.mean_axis(Axis(0)).expect("x must have at least one row")
+}
+
+/// Sum of squared deviations divided by `n - ddof`. Panics unless `ddof < n`.
+pub fn var<T: Float>(x: &Array2<T>, ddof: usize) -> Array1<T> {
+    assert!(ddof < x.nrows(), "ddof must be less than the number of rows");
+    let n = T::from(x.nrows() - ddof).unwrap();
+    let m = mean(x);
+    let mut v = Array1::<T>::zeros(x.ncols());
+    for row in x.genrows() {
+        Zip::from(&mut v).and(&row).and(&m).apply(|v, &xi, &mi| {
+            *v += (xi - mi) * (xi - mi);
+        });
+    }
+    v.map(|&vi| vi / n)
+}
+
+pub fn std<T: Float>(x: &Array2<T>, ddof: usize) -> Array1<T> {
+    var(x, ddof).map(|&v| v.sqrt())
+}
+
+/// Column sorted in ascending order. Panics on NaN.
+fn sorted<T: Float>(col: ArrayView1<T>) -> Vec<T> {
+    let mut v = col.to_vec();
+    v.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    v
+}
+
+/// `q`-quantile of sorted values, at position q (n - 1).
+fn quantile_sorted<T: Float>(v: &[T], q: T, interpolation: Interpolation) -> T {
+    let h = q * T::from(v.len() - 1).unwrap();
+    let lo = h.floor();
+    let frac = h - lo;
+    let i = lo.to_usize().unwrap();
+    let j = (i + 1).min(v.len() - 1);
+    match interpolation {
+        Interpolation::Linear => v[i] + (v[j] - v[i]) * frac,
+        Interpolation::Lower => v[i],
+        Interpolation::Higher if frac > T::zero() => v[j],
+        Interpolation::Higher => v[i],
+        Interpolation::Nearest if frac >= T::from(0.5).unwrap() => v[j],
+        Interpolation::Nearest => v[i],
+        Interpolation::Midpoint if frac > T::zero() => (v[i] + v[j]) / T::from(2).unwrap(),
+        Interpolation::Midpoint => v[i],
+    }
+}
+
+/// Column-wise `q`-quantile for `q` in [0, 1]. Panics on NaN or if `x` has no rows.
+pub fn quantile<T: Float>(x: &Array2<T>, q: T, interpolation: Interpolation) -> Array1<T> {
+    assert!(q >= T::zero() && q <= T::one(), "q must be in [0, 1]");
+    assert!(x.nrows() > 0, "x must have at least one row");
+    x.axis_iter(Axis(1)).map(|col| quantile_sorted(&sorted(col), q, interpolation)).collect()
+}
+
+pub fn median<T: Float>(x: &Array2<T>) -> Array1<T> {
+    quantile(x, T::from(0.5).unwrap(), Interpolation::Linear)
+}
+
+/// Median absolute deviation from the median. Multiply by 1.4826 to estimate the standard deviation of
+/// Gaussian data.
+pub fn mad<T: Float>(x: &Array2<T>) -> Array1<T> {
+    let m = median(x);
+    median(&(x - &m).map(|&d| d.abs()))
+}
+
+/// `n_features x n_features` covariance matrix, with the sums of products divided by `n - ddof`. Panics unless
+/// `ddof < n`.
+pub fn cov<T: Float>(x: &Array2<T>, ddof: usize) -> Array2<T> {
+    assert!(ddof < x.nrows(), "ddof must be less than the number of rows");
+    let n = T::from(x.nrows() - ddof).unwrap();
+    let xc = x - &mean(x);
+    xc.t().dot(&xc).map(|&c| c / n)
+}
+
+/// Pearson correlation matrix.
+pub fn corr<T: Float>(x: &Array2<T>) -> Array2<T> {
+    cov_to_corr(cov(x, 0))
+}
+
+fn cov_to_corr<T: Float>(mut c: Array2<T>) -> Array2<T> {
+    let d = c.diag().map(|&v| v.sqrt());
+    for ((i, j), cij) in c.indexed_iter_mut() {
+        *cij = if i == j { T::one() } else { *cij / (d[i] * d[j]) };
+    }
+    c
+}
+
+fn check_weights<T: Float>(x: &Array2<T>, w: &Array1<T>) {
+    assert_eq!(w.len(), x.nrows(), "w must have one weight per row of x");
+}
+
+/// Mean with non-negative weights `w` on the samples. Panics unless `w` has one weight per row of `x`.
+pub fn weighted_mean<T: Float>(x: &Array2<T>, w: &Array1<T>) -> Array1<T> {
+    check_weights(x, w);
+    let sw = w.sum();
+    w.dot(x).map(|&m| m / sw)
+}
+
+/// Weighted variance, treating `w` as frequency weights: the weighted sum of squares is divided by sum(w) - ddof.
+pub fn weighted_var<T: Float>(x: &Array2<T>, w: &Array1<T>, ddof: usize) -> Array1<T> {
+    let xc = x - &weighted_mean(x, w);
+    let n = w.sum() - T::from(ddof).unwrap();
+    w.dot(&xc.map(|&d| d * d)).map(|&v| v / n)
+}
+
+/// Weighted covariance matrix, with `w` and `ddof` as in `weighted_var`.
+pub fn weighted_cov<T: Float>(x: &Array2<T>, w: &Array1<T>, ddof: usize) -> Array2<T> {
+    let xc = x - &weighted_mean(x, w);
+    // Scaling both factors by sqrt(w) keeps the product exactly symmetric.
+    let xw = &xc * &w.map(|&wi| wi.sqrt()).insert_axis(Axis(1));
+    let n = w.sum() - T::from(ddof).unwrap();
+    xw.t().dot(&xw).map(|&c| c / n)
+}
+
+pub fn weighted_corr<T: Float>(x: &Array2<T>, w: &Array1<T>) -> Array2<T> {
+    cov_to_corr(weighted_cov(x, w, 0))
+}
+
+/// Column-wise weighted `q`-quantile: the smallest value whose cumulative weight reaches `q` of the total.
+/// Panics on NaN or if `x` has no rows.
+pub fn weighted_quantile<T: Float>(x: &Array2<T>, w: &Array1<T>, q: T) -> Array1<T> {
+    assert!(q >= T::zero() && q <= T::one(), "q must be in [0, 1]");
+    assert!(x.nrows() > 0, "x must have at least one row");
+    check_weights(x, w);
+    let target = q * w.sum();
+    x.axis_iter(Axis(1))
+        .map(|col| {
+            let mut idx: Vec<usize> = (0..col.len()).collect();
+            idx.sort_by(|&a, &b| col[a].partial_cmp(&col[b]).unwrap());
+            let mut acc = T::zero();
+            for &i in idx.iter() {
+                acc += w[i];
+                if acc >= target && w[i] > T::zero() {
+                    return col[i];
+                }
+            }
+            col[*idx.last().unwrap()]
+        })
+        .collect()
+}
+
+pub fn weighted_median<T: Float>(x: &Array2<T>, w: &Array1<T>) -> Array1<T> {
+    weighted_quantile(x, w, T::from(0.5).unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::assert_close;
+
+    // Reference values computed in exact rational arithmetic.
+
+    fn data() -> (Array2<f64>, Array1<f64>) {
+        let x = arr2(&[[2.0, 1.5], [4.0, -3.0], [4.0, 0.0], [5.0, 2.0], [7.0, 8.0], [9.0, 4.0]]);
+        (x, arr1(&[1.0, 2.0, 0.5, 1.0, 3.0, 1.5]))
+    }
+
+    #[test]
+    fn moments() {
+        let (x, _) = data();
+        let m = mean(&x);
+        assert_close(m[0], 5.166666666666667, 1e-15);
+        assert_close(m[1], 2.0833333333333335, 1e-15);
+        let cases = [
+            (0, 5.138888888888889, 11.534722222222221),
+            (1, 6.166666666666667, 13.841666666666667),
+            (5, 30.833333333333332, 69.20833333333333),
+        ];
+        for &(ddof, v0, v1) in cases.iter() {
+            let v = var(&x, ddof);
+            assert_close(v[0], v0, 1e-15);
+            assert_close(v[1], v1, 1e-15);
+            assert_close(std(&x, ddof)[1], v1.sqrt(), 1e-15);
+        }
+        let c = cov(&x, 1);
+        assert_close(c[[0, 1]], 5.683333333333334, 1e-15);
+        assert_eq!(c[[0, 1]], c[[1, 0]]);
+        assert_close(c[[1, 1]], 13.841666666666667, 1e-15);
+        let r = corr(&x);
+        assert_close(r[[0, 1]], 0.6151537822082939, 1e-15);
+        assert_eq!(r[[0, 0]], 1.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "ddof")]
+    fn var_ddof_too_large() {
+        var(&data().0, 6);
+    }
+
+    #[test]
+    #[should_panic(expected = "at least one row")]
+    fn mean_empty() {
+        mean(&Array2::<f64>::zeros((0, 2)));
+    }
+
+    #[test]
+    #[should_panic(expected = "at least one row")]
+    fn median_empty() {
+        median(&Array2::<f64>::zeros((0, 2)));
+    }
+
+    #[test]
+    #[should_panic(expected = "one weight per row")]
+    fn weighted_var_length_mismatch() {
+        weighted_var(&data().0, &arr1(&[1.0, 2.0]), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "one weight per row")]
+    fn weighted_quantile_length_mismatch() {
+        weighted_quantile(&data().0, &arr1(&[1.0, 2.0]), 0.5);
+    }
+
+    #[test]
+    fn quantiles() {
+        // The second column sorted is -3, 0, 1.5, 2, 4, 8; q = 0.35 falls 3/4 of the way from 0 to 1.5.
+        let (x, _) = data();
+        let cases = [
+            (Interpolation::Linear, 1.125, 0.375),
+            (Interpolation::Lower, 0.0, 0.0),
+            (Interpolation::Higher, 1.5, 1.5),
+            (Interpolation::Nearest, 1.5, 0.0),
+            (Interpolation::Midpoint, 0.75, 0.75),
+        ];
+        for &(interpolation, q35, q25) in cases.iter() {
+            assert_eq!(quantile(&x, 0.35, interpolation)[1], q35);
+            assert_eq!(quantile(&x, 0.25, interpolation)[1], q25);
+            // Exactly on an order statistic every rule agrees.
+            assert_eq!(quantile(&x, 0.4, interpolation), arr1(&[4.0, 1.5]));
+        }
+        // Nearest rounds a tie up.
+        assert_eq!(quantile(&x, 0.3, Interpolation::Nearest)[1], 1.5);
+        assert_eq!(quantile(&x, 0.0, Interpolation::Linear), arr1(&[2.0, -3.0]));
+        assert_eq!(quantile(&x, 1.0, Interpolation::Linear), arr1(&[9.0, 8.0]));
+        assert_eq!(median(&x), arr1(&[4.5, 1.75]));
+        assert_eq!(mad(&x), arr1(&[1.5, 2.0]));
+    }
+
+    #[test]
+    fn weighted() {
+        let (x, w) = data();
+        let m = weighted_mean(&x, &w);
+        assert_close(m[0], 5.722222222222222, 1e-15);
+        assert_close(m[1], 3.0555555555555554, 1e-15);
+        let v = weighted_var(&x, &w, 1);
+        assert_close(v[0], 5.350694444444445, 1e-15);
+        assert_close(v[1], 19.52777777777778, 1e-15);
+        let c = weighted_cov(&x, &w, 1);
+        assert_close(c[[0, 1]], 6.704861111111111, 1e-15);
+        assert_eq!(c[[0, 1]], c[[1, 0]]);
+        assert_close(c[[1, 1]], v[1], 1e-15);
+        assert_close(weighted_corr(&x, &w)[[0, 1]], 0.6559312141747533, 1e-15);
+        assert_eq!(weighted_median(&x, &w), arr1(&[5.0, 2.0]));
+        assert_eq!(weighted_quantile(&x, &w, 0.0), arr1(&[2.0, -3.0]));
+        assert_eq!(weighted_quantile(&x, &w, 1.0), arr1(&[9.0, 8.0]));
+        // Unit weights reduce to the unweighted estimates.
+        let ones = Array1::ones(6);
+        let (v, u) = (weighted_var(&x, &ones, 1), var(&x, 1));
+        assert_close(v[0], u[0], 1e-15);
+        assert_close(weighted_corr(&x, &ones)[[0, 1]], corr(&x)[[0, 1]], 1e-14);
+    }
+}
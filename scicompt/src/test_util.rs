@@ -0,0 +1,9 @@
+//! Helpers shared by the unit tests.
+//!
+//! Reference values in the tests are computed with mpmath at 50 digits unless a test says otherwise.
+
+/// Asserts that `actual` is within relative error `tol` of `expected`.
+pub fn assert_close(actual: f64, expected: f64, tol: f64) {
+    let err = (actual - expected).abs() / expected.abs().max(1e-300);
+    assert!(err <= tol, "{} != {} (relative error {:e})", actual, expected, err);
+}
@@ -0,0 +1,103 @@
+use crate::consts::FloatConst;
+use crate::gamma::ln_gamma;
+
+/// Euler-Maclaurin summation of sum_k (q + k)^-s, valid for s != 1 and q > 0.
+fn zeta_em<T: FloatConst>(s: T, q: T) -> T {
+    let one = T::one();
+    // Sum directly until the tail starts at w >= max(10, s), where the correction terms decay quickly.
+    let lim = s.max(T::from(10).unwrap());
+    let mut w = q;
+    let mut sum = T::zero();
+    while w < lim {
+        sum += w.powf(-s);
+        w += one;
+    }
+    let w_s = w.powf(-s);
+    sum += w * w_s / (s - one) + T::from(0.5).unwrap() * w_s;
+    // B_2j / (2j)! * s (s + 1) ... (s + 2j - 2) * w^(-s - 2j + 1)
+    let w2 = w * w;
+    let mut a = s * w_s / (w + w);
+    for (j, &b) in T::BERNOULLI_2K.iter().enumerate() {
+        let term = b * a;
+        sum += term;
+        if term.abs() <= sum.abs() * T::epsilon() {
+            break;
+        }
+        let k = T::from(2 * j + 2).unwrap();
+        a *= (s + k - one) * (s + k) / ((k + one) * (k + T::from(2).unwrap()) * w2);
+    }
+    sum
+}
+
+/// Riemann zeta function.
+pub fn zeta<T: FloatConst>(s: T) -> T {
+    let one = T::one();
+    if s == one {
+        return T::infinity();
+    }
+    if s >= T::zero() {
+        return zeta_em(s, one);
+    }
+    // Functional equation zeta(s) = 2^s pi^(s - 1) sin(pi s / 2) gamma(1 - s) zeta(1 - s).
+    let two = T::from(2).unwrap();
+    let half_s = s / two;
+    if half_s == half_s.floor() {
+        return T::zero();
+    }
+    let pi = T::from(std::f64::consts::PI).unwrap();
+    let sin = (pi * (half_s % two)).sin();
+    let ln_mag = s * two.ln() + (s - one) * pi.ln() + ln_gamma(one - s);
+    sin * ln_mag.exp() * zeta_em(one - s, one)
+}
+
+/// Hurwitz zeta function sum_k (q + k)^-s, for s > 1 and q > 0.
+pub fn hurwitz_zeta<T: FloatConst>(s: T, q: T) -> T {
+    if s <= T::one() || q <= T::zero() {
+        return T::nan();
+    }
+    zeta_em(s, q)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::assert_close;
+
+    #[test]
+    fn zeta_values() {
+        let cases = [
+            (2.0, 1.6449340668482264),
+            (0.5, -1.4603545088095868),
+            (1.0001, 10000.577222947539),
+            (3.5, 1.1267338673170566),
+            (50.0, 1.0000000000000009),
+            (0.0, -0.5),
+            (-1.0, -0.08333333333333333),
+            (-0.5, -0.20788622497735457),
+            (-3.5, 0.004441011335479432),
+            (-20.5, -108.21747505877606),
+        ];
+        for &(s, z) in cases.iter() {
+            assert_close(zeta(s), z, 1e-14);
+        }
+        assert_eq!(zeta(-2.0), 0.0);
+        assert_eq!(zeta(-10.0), 0.0);
+        assert_eq!(zeta(1.0), f64::INFINITY);
+    }
+
+    #[test]
+    fn hurwitz_zeta_values() {
+        let cases = [
+            (2.0, 0.5, 4.934802200544679),
+            (3.0, 2.5, 0.1181020258208637),
+            (1.5, 10.0, 0.6486616319415704),
+            (4.0, 1e-3, 1000000000001.0781),
+            (30.0, 3.0, 4.857804189667247e-15),
+        ];
+        for &(s, q, z) in cases.iter() {
+            assert_close(hurwitz_zeta(s, q), z, 1e-14);
+        }
+        assert_eq!(hurwitz_zeta(2.5, 1.0), zeta(2.5));
+        assert!(hurwitz_zeta(1.0f64, 2.0).is_nan() && hurwitz_zeta(2.0f64, 0.0).is_nan());
+    }
+}